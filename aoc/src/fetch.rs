@@ -0,0 +1,125 @@
+use crate::{http, Config};
+use std::fs;
+use std::io::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Where the runner of the day expects its input.
+pub fn input_path(day: u32) -> PathBuf {
+    PathBuf::from(format!("day{day}/src/input.txt"))
+}
+
+/// Pristine copy of the downloaded input, used to avoid refetching and to
+/// notice local edits of the input file.
+pub fn cache_path(day: u32) -> PathBuf {
+    PathBuf::from(format!(".aoc/inputs/day{day}.txt"))
+}
+
+pub fn fetch(config: &Config, day: u32, force: bool) -> Result<()> {
+    let target = input_path(day);
+    let day_dir = target.parent().unwrap();
+    if !day_dir.is_dir() {
+        return Err(Error::other(format!(
+            "{} does not exist, is day{day} a workspace member?",
+            day_dir.display()
+        )));
+    }
+
+    let cache = cache_path(day);
+    let input = match fs::read(&cache) {
+        Ok(input) if !force => {
+            println!("using cached input {}", cache.display());
+            input
+        }
+        _ => {
+            let input = download(config, day)?;
+            fs::create_dir_all(cache.parent().unwrap())?;
+            fs::write(&cache, &input)?;
+            input
+        }
+    };
+
+    write_input(&target, &input, force)
+}
+
+fn download(config: &Config, day: u32) -> Result<Vec<u8>> {
+    let session = config.session()?;
+    let url = format!("{}/day/{day}/input", config.base_url);
+    println!("fetching {url}");
+    let response = http::get(&url, session)?;
+    if response.status != 200 {
+        return Err(Error::other(format!(
+            "server responded with {}: {}",
+            response.status,
+            response.text().trim()
+        )));
+    }
+
+    Ok(response.body)
+}
+
+fn write_input(target: &Path, input: &[u8], force: bool) -> Result<()> {
+    match fs::read(target) {
+        Ok(existing) if existing == input => {
+            println!("{} is up to date", target.display());
+            return Ok(());
+        }
+        Ok(_) if !force => {
+            return Err(Error::other(format!(
+                "{} differs from the fetched input, refusing to overwrite it (use --force)",
+                target.display()
+            )));
+        }
+        _ => {}
+    }
+
+    fs::write(target, input)?;
+    println!("wrote {}", target.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::testing::{Scratch, start};
+    use std::time::Duration;
+
+    #[test]
+    fn downloads_caches_and_protects_edited_input() {
+        let scratch = Scratch::new("fetch");
+        let stub_dir = scratch.dir.join("stub");
+        fs::create_dir_all(&stub_dir).unwrap();
+        fs::write(stub_dir.join("day3.txt"), "987654321\n").unwrap();
+        fs::create_dir_all("day3/src").unwrap();
+        let config = start(&stub_dir, Duration::ZERO);
+
+        fetch(&config, 3, false).unwrap();
+        assert_eq!(fs::read_to_string(input_path(3)).unwrap(), "987654321\n");
+        assert_eq!(fs::read_to_string(cache_path(3)).unwrap(), "987654321\n");
+
+        // served from the cache, the server copy is not looked at again
+        fs::write(stub_dir.join("day3.txt"), "123\n").unwrap();
+        fs::remove_file(input_path(3)).unwrap();
+        fetch(&config, 3, false).unwrap();
+        assert_eq!(fs::read_to_string(input_path(3)).unwrap(), "987654321\n");
+
+        fs::write(input_path(3), "edited\n").unwrap();
+        let err = fetch(&config, 3, false).unwrap_err();
+        assert!(err.to_string().contains("refusing to overwrite"), "{err}");
+        assert_eq!(fs::read_to_string(input_path(3)).unwrap(), "edited\n");
+
+        fetch(&config, 3, true).unwrap();
+        assert_eq!(fs::read_to_string(input_path(3)).unwrap(), "123\n");
+    }
+
+    #[test]
+    fn reports_missing_input_and_day() {
+        let scratch = Scratch::new("fetch-missing");
+        let config = start(&scratch.dir, Duration::ZERO);
+
+        assert!(fetch(&config, 4, false).unwrap_err().to_string().contains("is day4 a workspace member"));
+        fs::create_dir_all("day4/src").unwrap();
+        let err = fetch(&config, 4, false).unwrap_err();
+        assert!(err.to_string().contains("server responded with 404"), "{err}");
+        assert!(!input_path(4).exists());
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::io::Result;
+use util::history::{self, Run};
+
+/// Prints the recorded runs of a day per part and flags regressions: solves
+/// slower than `threshold` percent over the best earlier time, and answers
+/// which differ from an earlier run on the same input. Runs are only compared
+/// with runs given the same options.
+pub fn show(day: u32, part: Option<u32>, threshold: f64) -> Result<()> {
+    let day = format!("day{day}");
+    let runs = history::load(&day)?;
+    if runs.is_empty() {
+        println!("no recorded runs of {day}");
+        return Ok(());
+    }
+
+    for p in [1, 2] {
+        if part.is_some_and(|part| part != p) {
+            continue;
+        }
+        let runs = runs.iter().filter(|r| r.part == p).collect::<Vec<_>>();
+        if runs.is_empty() {
+            continue;
+        }
+
+        println!("{day} part {p}");
+        println!(
+            "{:<19}  {:<9}  {:<8}  {:>20}  {:>10}  {:>10}  {:<16}  notes",
+            "time", "commit", "input", "answer", "parse ms", "solve ms", "options"
+        );
+        // best time and last answer per input hash and options seen so far
+        let mut best = HashMap::<Key, f64>::new();
+        let mut answers = HashMap::<Key, &str>::new();
+        for run in runs.iter() {
+            let notes = notes(run, &best, &answers, threshold);
+            let line = format!(
+                "{:<19}  {:<9}  {:<8}  {:>20}  {:>10.3}  {:>10.3}  {:<16}  {}",
+                format_time(run.time),
+                run.commit,
+                format!("{:08x}", run.input_hash >> 32),
+                run.answer,
+                run.parse_secs * 1000.0,
+                run.solve_secs * 1000.0,
+                run.options,
+                notes.join(", ")
+            );
+            println!("{}", line.trim_end());
+            let entry = best.entry(key(run)).or_insert(run.solve_secs);
+            *entry = entry.min(run.solve_secs);
+            answers.insert(key(run), &run.answer);
+        }
+
+        let last = runs.last().unwrap();
+        println!(
+            "{} runs, best {:.3} ms, last {:.3} ms",
+            runs.len(),
+            best[&key(last)] * 1000.0,
+            last.solve_secs * 1000.0
+        );
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Runs are compared per input and options.
+type Key<'a> = (u64, &'a str);
+
+fn key(run: &Run) -> Key<'_> {
+    (run.input_hash, &run.options)
+}
+
+fn notes(run: &Run, best: &HashMap<Key, f64>, answers: &HashMap<Key, &str>, threshold: f64) -> Vec<String> {
+    let mut notes = vec![];
+    if let Some(&best) = best.get(&key(run))
+        && best > 0.0
+    {
+        let slower = (run.solve_secs / best - 1.0) * 100.0;
+        if slower > threshold {
+            notes.push(format!("SLOW +{slower:.0}% vs best {:.3} ms", best * 1000.0));
+        }
+    }
+    if let Some(&answer) = answers.get(&key(run))
+        && answer != run.answer
+    {
+        notes.push(format!("ANSWER CHANGED from {answer}"));
+    }
+    notes
+}
+
+/// Formats unix seconds as UTC `YYYY-MM-DD HH:MM:SS`.
+fn format_time(time: u64) -> String {
+    let days = (time / 86400) as i64;
+    let secs = time % 86400;
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
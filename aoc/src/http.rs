@@ -0,0 +1,235 @@
+use std::io::{BufRead, BufReader, Error, Read, Result, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+pub fn get(url: &str, session: &str) -> Result<Response> {
+    request("GET", url, session, None)
+}
+
+pub fn post_form(url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response> {
+    let body = form
+        .iter()
+        .map(|(k, v)| format!("{}={}", url_encode(k), url_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+    request("POST", url, session, Some(body))
+}
+
+fn request(method: &str, url: &str, session: &str, body: Option<String>) -> Result<Response> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        plain_request(method, rest, session, body)
+    } else if url.starts_with("https://") {
+        // no TLS in std, let curl do the handshake
+        curl_request(method, url, session, body)
+    } else {
+        Err(Error::other(format!("unsupported url scheme: {url}")))
+    }
+}
+
+fn plain_request(method: &str, url: &str, session: &str, body: Option<String>) -> Result<Response> {
+    let (host, path) = match url.find('/') {
+        Some(slash) => url.split_at(slash),
+        None => (url, "/"),
+    };
+    let addr = if host.contains(':') {
+        host.to_owned()
+    } else {
+        format!("{host}:80")
+    };
+
+    let mut stream = TcpStream::connect(addr)?;
+    write!(stream, "{method} {path} HTTP/1.1\r\n")?;
+    write!(stream, "Host: {host}\r\n")?;
+    write!(stream, "Cookie: session={session}\r\n")?;
+    write!(stream, "User-Agent: aoc2025 runner\r\n")?;
+    write!(stream, "Connection: close\r\n")?;
+    if let Some(body) = &body {
+        write!(stream, "Content-Type: application/x-www-form-urlencoded\r\n")?;
+        write!(stream, "Content-Length: {}\r\n", body.len())?;
+    }
+    write!(stream, "\r\n")?;
+    if let Some(body) = &body {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line)?;
+    let status = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| Error::other(format!("malformed status line: {status_line:?}")))?;
+
+    let headers = read_headers(&mut reader)?;
+    let chunked = header(&headers, "transfer-encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked"));
+
+    let mut body = vec![];
+    if chunked {
+        read_chunked(&mut reader, &mut body)?;
+    } else if let Some(len) = header(&headers, "content-length").and_then(|v| v.parse().ok()) {
+        body.resize(len, 0);
+        reader.read_exact(&mut body)?;
+    } else {
+        reader.read_to_end(&mut body)?;
+    }
+
+    Ok(Response { status, body })
+}
+
+fn curl_request(method: &str, url: &str, session: &str, body: Option<String>) -> Result<Response> {
+    if session.contains(['\r', '\n']) {
+        return Err(Error::other("session must be a single line"));
+    }
+
+    // the cookie goes through stdin, arguments are visible to every local user
+    let mut cmd = Command::new("curl");
+    cmd.args(["--silent", "--show-error", "--request", method])
+        .args(["--header", "@-"])
+        .args(["--user-agent", "aoc2025 runner"])
+        .args(["--write-out", "\n%{http_code}"]);
+    if let Some(body) = &body {
+        cmd.args(["--data", body]);
+    }
+    let mut child = cmd
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "Cookie: session={session}")?;
+    drop(stdin);
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "curl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let mut body = output.stdout;
+    let status_start = body.iter().rposition(|&c| c == b'\n').unwrap_or(0);
+    let status = str::from_utf8(&body[status_start..])
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .ok_or_else(|| Error::other("curl did not report a status code"))?;
+    body.truncate(status_start);
+
+    Ok(Response { status, body })
+}
+
+pub fn read_headers(reader: &mut impl BufRead) -> Result<Vec<(String, String)>> {
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+
+    Ok(headers)
+}
+
+pub fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn read_chunked(reader: &mut impl BufRead, body: &mut Vec<u8>) -> Result<()> {
+    loop {
+        let mut size_line = String::new();
+        reader.read_line(&mut size_line)?;
+        let size = size_line.trim().split(';').next().unwrap_or("");
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| Error::other(format!("malformed chunk size: {size_line:?}")))?;
+        if size == 0 {
+            // trailers are not interesting
+            read_headers(reader)?;
+            return Ok(());
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        let mut crlf = [0u8; 2];
+        reader.read_exact(&mut crlf)?;
+    }
+}
+
+pub fn url_encode(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.bytes() {
+        match c {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => res.push(c as char),
+            c => res.push_str(&format!("%{c:02X}")),
+        }
+    }
+    res
+}
+
+pub fn url_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => res.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(c) => {
+                        res.push(c);
+                        i += 2;
+                    }
+                    None => res.push(b'%'),
+                }
+            }
+            c => res.push(c),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&res).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_encoding_round_trips() {
+        let form = "answer=12 34&x=ü/%";
+        assert_eq!(url_encode(form), "answer%3D12%2034%26x%3D%C3%BC%2F%25");
+        assert_eq!(url_decode(&url_encode(form)), form);
+        assert_eq!(url_decode("a+b%2"), "a b%2");
+    }
+
+    #[test]
+    fn reads_chunked_bodies() {
+        let mut reader = "4\r\nWiki\r\n5;ext=1\r\npedia\r\n0\r\nTrailer: x\r\n\r\n".as_bytes();
+        let mut body = vec![];
+        read_chunked(&mut reader, &mut body).unwrap();
+        assert_eq!(body, b"Wikipedia");
+    }
+}
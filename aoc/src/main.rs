@@ -0,0 +1,112 @@
+mod fetch;
+mod history;
+mod http;
+mod scaffold;
+mod stub;
+mod submit;
+
+use std::env::{args, var};
+use std::io::{Error, Result};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Duration;
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2025";
+const USAGE: &str = "usage:
+    aoc new <day>
+    aoc fetch <day> [--force]
+    aoc submit <day> <part> [--answer <answer>]
+    aoc history <day> [--part <part>] [--threshold <percent>]
+    aoc stub [--addr <host:port>] [--dir <dir>] [--cooldown <secs>]
+
+environment:
+    AOC_BASE_URL  puzzle server, defaults to https://adventofcode.com/2025
+    AOC_SESSION   session cookie of the logged in user";
+
+pub struct Config {
+    base_url: String,
+    session: Option<String>,
+}
+
+impl Config {
+    fn from_env() -> Self {
+        let base_url = var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_owned());
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: var("AOC_SESSION").ok().filter(|s| !s.is_empty()),
+        }
+    }
+
+    fn session(&self) -> Result<&str> {
+        self.session
+            .as_deref()
+            .ok_or_else(|| Error::other("AOC_SESSION is not set"))
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let args = args().skip(1).collect::<Vec<_>>();
+    let arg = |i: usize| args.get(i).map(String::as_str);
+    let config = Config::from_env();
+    let flag = |name: &str| args.iter().any(|a| a == name);
+    let option = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+
+    match arg(0) {
+        Some("new") => scaffold::new_day(parse_day(arg(1))?),
+        Some("fetch") => fetch::fetch(&config, parse_day(arg(1))?, flag("--force")),
+        Some("submit") => submit::submit(
+            &config,
+            parse_day(arg(1))?,
+            parse_part(arg(2))?,
+            option("--answer"),
+        ),
+        Some("history") => history::show(
+            parse_day(arg(1))?,
+            option("--part").map(|p| parse_part(Some(p))).transpose()?,
+            option("--threshold").map(|t| parse_number(t, "threshold")).transpose()?.unwrap_or(20.0),
+        ),
+        Some("stub") => {
+            let cooldown = option("--cooldown").map(|c| parse_number(c, "cooldown")).transpose()?.unwrap_or(60);
+            stub::Stub::new(
+                Path::new(option("--dir").unwrap_or(".aoc/stub")),
+                Duration::from_secs(cooldown),
+            )
+            .serve(TcpListener::bind(option("--addr").unwrap_or("127.0.0.1:8025"))?)
+        }
+        _ => Err(Error::other(USAGE)),
+    }
+}
+
+fn parse_day(arg: Option<&str>) -> Result<u32> {
+    arg.and_then(|d| d.trim_start_matches("day").parse().ok())
+        .filter(|d| (1..=25).contains(d))
+        .ok_or_else(|| Error::other("expected a day between 1 and 25"))
+}
+
+fn parse_part(arg: Option<&str>) -> Result<u32> {
+    arg.and_then(|p| p.parse().ok())
+        .filter(|p| matches!(p, 1 | 2))
+        .ok_or_else(|| Error::other("expected part to be 1 or 2"))
+}
+
+fn parse_number<T: FromStr>(arg: &str, name: &str) -> Result<T> {
+    arg.parse()
+        .map_err(|_| Error::other(format!("invalid {name} {arg:?}, expected a number")))
+}
@@ -0,0 +1,70 @@
+use std::fs;
+use std::io::{Error, Result};
+use std::path::{Path, PathBuf};
+
+const CARGO_TEMPLATE: &str = include_str!("template/Cargo.toml.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("template/main.rs.tmpl");
+
+/// Creates `day{N}` from the templates and registers it in the workspace.
+pub fn new_day(day: u32) -> Result<()> {
+    let name = format!("day{day}");
+    let dir = PathBuf::from(&name);
+    if dir.exists() {
+        return Err(Error::other(format!("{} already exists", dir.display())));
+    }
+
+    let manifest = fs::read_to_string("Cargo.toml")?;
+    let manifest = add_member(&manifest, &name)?;
+
+    fs::create_dir_all(dir.join("src"))?;
+    write(&dir.join("Cargo.toml"), &render(CARGO_TEMPLATE, day))?;
+    write(&dir.join("src/main.rs"), &render(MAIN_TEMPLATE, day))?;
+    write(&dir.join("src/input_test.txt"), "")?;
+    fs::write("Cargo.toml", manifest)?;
+    println!("added {name} to workspace members");
+    println!("run `aoc fetch {day}` to get the input");
+
+    Ok(())
+}
+
+fn render(template: &str, day: u32) -> String {
+    template.replace("{{day}}", &day.to_string())
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)?;
+    println!("created {}", path.display());
+    Ok(())
+}
+
+/// Inserts `name` into the single line `members = [...]` list, keeping it sorted.
+fn add_member(manifest: &str, name: &str) -> Result<String> {
+    let mut lines = manifest.lines().map(str::to_owned).collect::<Vec<_>>();
+    let line = lines
+        .iter_mut()
+        .find(|l| l.trim_start().starts_with("members"))
+        .ok_or_else(|| Error::other("no workspace members in Cargo.toml"))?;
+
+    let start = line.find('[').ok_or_else(|| Error::other("members is not a list"))?;
+    let end = line.rfind(']').ok_or_else(|| Error::other("members must fit on one line"))?;
+    let mut members = line[start + 1..end]
+        .split(',')
+        .map(|m| m.trim().trim_matches('"'))
+        .filter(|m| !m.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    if members.iter().any(|m| m == name) {
+        return Ok(manifest.to_owned());
+    }
+    members.push(name.to_owned());
+    members.sort();
+
+    let list = members.iter().map(|m| format!("\"{m}\", ")).collect::<String>();
+    *line = format!("{}[{}]{}", &line[..start], list, &line[end + 1..]);
+
+    let mut res = lines.join("\n");
+    if manifest.ends_with('\n') {
+        res.push('\n');
+    }
+    Ok(res)
+}
@@ -0,0 +1,195 @@
+use crate::http;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Result, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Minimal stand-in for the puzzle server, so the client can be exercised offline.
+///
+/// Inputs are served from `{dir}/day{N}.txt`, answers are checked against the
+/// lines of `{dir}/day{N}.answers` (first line is part 1, second is part 2).
+pub struct Stub {
+    dir: PathBuf,
+    cooldown: Duration,
+    locked_until: Option<Instant>,
+}
+
+impl Stub {
+    pub fn new(dir: &Path, cooldown: Duration) -> Self {
+        Self {
+            dir: dir.to_owned(),
+            cooldown,
+            locked_until: None,
+        }
+    }
+
+    pub fn serve(mut self, listener: TcpListener) -> Result<()> {
+        println!("stub server listening on http://{}", listener.local_addr()?);
+        for stream in listener.incoming() {
+            let stream = stream?;
+            if let Err(err) = self.handle(stream) {
+                eprintln!("stub: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, mut stream: TcpStream) -> Result<()> {
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let headers = http::read_headers(&mut reader)?;
+        let len = http::header(&headers, "content-length")
+            .and_then(|l| l.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body)?;
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let path = parts.next().unwrap_or("");
+        println!("stub: {method} {path}");
+
+        let session = http::header(&headers, "cookie")
+            .and_then(|c| c.split(';').find_map(|c| c.trim().strip_prefix("session=")))
+            .filter(|s| !s.is_empty());
+
+        let (status, body) = match (method, route(path), session) {
+            (_, _, None) => (400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n".to_owned()),
+            ("GET", Some((day, "input")), Some(_)) => match fs::read_to_string(self.input_file(day)) {
+                Ok(input) => (200, input),
+                Err(_) => (404, "404 Not Found\n".to_owned()),
+            },
+            ("POST", Some((day, "answer")), Some(_)) => (200, self.answer(day, &String::from_utf8_lossy(&body))),
+            _ => (404, "404 Not Found\n".to_owned()),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {status} {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            if status == 200 { "OK" } else { "Error" },
+            body.len()
+        )?;
+        stream.flush()
+    }
+
+    fn answer(&mut self, day: u32, form: &str) -> String {
+        let field = |name: &str| {
+            form.split('&')
+                .filter_map(|kv| kv.split_once('='))
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| http::url_decode(v))
+        };
+
+        let now = Instant::now();
+        if let Some(locked_until) = self.locked_until
+            && locked_until > now
+        {
+            let left = (locked_until - now).as_secs() + 1;
+            return article(&format!(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have {}m {}s left to wait.",
+                left / 60,
+                left % 60
+            ));
+        }
+
+        let expected = fs::read_to_string(self.dir.join(format!("day{day}.answers"))).unwrap_or_default();
+        let level = field("level").and_then(|l| l.parse::<usize>().ok()).unwrap_or(0);
+        let Some(expected) = level.checked_sub(1).and_then(|l| expected.lines().nth(l)) else {
+            return article("You don't seem to be solving the right level.  Did you already complete it?");
+        };
+        let answer = field("answer").unwrap_or_default();
+        if answer.trim() == expected.trim() {
+            return article("That's the right answer!  You are one gold star closer to decorating the North Pole.");
+        }
+
+        self.locked_until = Some(now + self.cooldown);
+        let hint = match (answer.trim().parse::<u128>(), expected.trim().parse::<u128>()) {
+            (Ok(a), Ok(e)) if a > e => "  your answer is too high.",
+            (Ok(a), Ok(e)) if a < e => "  your answer is too low.",
+            _ => "",
+        };
+        article(&format!(
+            "That's not the right answer;{hint}  Please wait {} seconds before trying again.",
+            self.cooldown.as_secs()
+        ))
+    }
+
+    fn input_file(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("day{day}.txt"))
+    }
+}
+
+fn article(text: &str) -> String {
+    format!("<html><body><main><article><p>{text}</p></article></main></body></html>\n")
+}
+
+/// Splits `.../day/{N}/{action}` into day and action.
+fn route(path: &str) -> Option<(u32, &str)> {
+    let mut segments = path.rsplit('/');
+    let action = segments.next()?;
+    let day = segments.next()?.parse().ok()?;
+    (segments.next()? == "day").then_some((day, action))
+}
+
+/// Test helpers running the client against a [`Stub`] in a scratch directory.
+#[cfg(test)]
+pub mod testing {
+    use super::Stub;
+    use crate::Config;
+    use std::env;
+    use std::fs;
+    use std::net::TcpListener;
+    use std::path::{Path, PathBuf};
+    use std::process;
+    use std::sync::{Mutex, MutexGuard};
+    use std::thread;
+    use std::time::Duration;
+
+    // the client works relative to the current directory, which is shared by all tests
+    static CWD: Mutex<()> = Mutex::new(());
+
+    /// Current directory switched to an empty scratch directory until dropped.
+    pub struct Scratch {
+        _lock: MutexGuard<'static, ()>,
+        previous: PathBuf,
+        pub dir: PathBuf,
+    }
+
+    impl Scratch {
+        pub fn new(name: &str) -> Self {
+            let lock = CWD.lock().unwrap_or_else(|err| err.into_inner());
+            let dir = env::temp_dir().join(format!("aoc-test-{}-{name}", process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            let previous = env::current_dir().unwrap();
+            env::set_current_dir(&dir).unwrap();
+            Self {
+                _lock: lock,
+                previous,
+                dir,
+            }
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = env::set_current_dir(&self.previous);
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    /// Serves `dir` on a free local port and returns a config pointing at it.
+    pub fn start(dir: &Path, cooldown: Duration) -> Config {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stub = Stub::new(dir, cooldown);
+        thread::spawn(move || stub.serve(listener));
+        Config {
+            base_url: format!("http://{addr}"),
+            session: Some("test".to_owned()),
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use crate::{Config, http};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{Error, Result, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use util::history::now;
+
+pub fn history_path() -> PathBuf {
+    PathBuf::from(".aoc/guesses.tsv")
+}
+
+pub fn submit(config: &Config, day: u32, part: u32, answer: Option<&str>) -> Result<()> {
+    let answer = match answer {
+        Some(answer) => answer.to_owned(),
+        None => compute_answer(day, part)?,
+    };
+    let history = GuessHistory::load()?;
+    history.check(day, part, &answer, now())?;
+
+    let session = config.session()?;
+    let url = format!("{}/day/{day}/answer", config.base_url);
+    println!("submitting {answer} for day {day} part {part}");
+    let response = http::post_form(&url, session, &[("level", &part.to_string()), ("answer", &answer)])?;
+    if response.status != 200 {
+        return Err(Error::other(format!(
+            "server responded with {}: {}",
+            response.status,
+            response.text().trim()
+        )));
+    }
+
+    let (verdict, wait) = parse_response(&response.text());
+    let guess = Guess {
+        time: now(),
+        day,
+        part,
+        answer,
+        verdict,
+        wait_until: wait.map(|w| now() + w).unwrap_or(0),
+    };
+    GuessHistory::append(&guess)?;
+
+    println!("{}", guess.verdict);
+    if let Some(wait) = wait {
+        println!("next submission possible in {wait}s");
+    }
+
+    Ok(())
+}
+
+/// Runs the day binary and takes the answer from its first output line.
+fn compute_answer(day: u32, part: u32) -> Result<String> {
+    println!("running day{day} part {part}");
+    let output = Command::new("cargo")
+        .args(["run", "--release", "--quiet", "--package"])
+        .arg(format!("day{day}"))
+        .arg("--")
+        .arg(part.to_string())
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "day{day} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_owned())
+        .filter(|line| !line.is_empty())
+        .ok_or_else(|| Error::other(format!("day{day} did not print an answer")))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited,
+    /// The part is not open, e.g. part 2 before part 1 is solved. Nothing
+    /// is known about the answer.
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::RateLimited => "rate-limited",
+            Verdict::WrongLevel => "wrong-level",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        [
+            Verdict::Correct,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wrong,
+            Verdict::RateLimited,
+            Verdict::WrongLevel,
+            Verdict::Unknown,
+        ]
+        .into_iter()
+        .find(|v| v.as_str() == s)
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Extracts the verdict and the requested wait in seconds from the response page.
+pub fn parse_response(page: &str) -> (Verdict, Option<u64>) {
+    let text = page.to_ascii_lowercase();
+    let verdict = if text.contains("that's the right answer") {
+        Verdict::Correct
+    } else if text.contains("answer too recently") {
+        Verdict::RateLimited
+    } else if text.contains("too high") {
+        Verdict::TooHigh
+    } else if text.contains("too low") {
+        Verdict::TooLow
+    } else if text.contains("not the right answer") {
+        Verdict::Wrong
+    } else if text.contains("don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    };
+
+    (verdict, parse_wait(&text))
+}
+
+/// Understands "you have 1m 5s left to wait" and "please wait 5 minutes".
+fn parse_wait(text: &str) -> Option<u64> {
+    if let Some(end) = text.find(" left to wait") {
+        let start = text[..end].rfind("you have ")? + "you have ".len();
+        let mut secs = 0;
+        for part in text[start..end].split_whitespace() {
+            let (num, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let num = num.parse::<u64>().ok()?;
+            secs += match unit {
+                "h" => num * 3600,
+                "m" => num * 60,
+                _ => num,
+            };
+        }
+        return Some(secs);
+    }
+
+    let start = text.find("please wait ")? + "please wait ".len();
+    let mut words = text[start..].split_whitespace();
+    let num = match words.next()? {
+        "one" => 1,
+        "two" => 2,
+        "five" => 5,
+        "ten" => 10,
+        n => n.parse().ok()?,
+    };
+    let unit = words.next()?;
+    Some(if unit.starts_with("minute") { num * 60 } else { num })
+}
+
+struct Guess {
+    time: u64,
+    day: u32,
+    part: u32,
+    answer: String,
+    verdict: Verdict,
+    wait_until: u64,
+}
+
+impl Guess {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        Some(Guess {
+            time: fields.next()?.parse().ok()?,
+            day: fields.next()?.parse().ok()?,
+            part: fields.next()?.parse().ok()?,
+            answer: fields.next()?.to_owned(),
+            verdict: Verdict::parse(fields.next()?)?,
+            wait_until: fields.next()?.parse().ok()?,
+        })
+    }
+}
+
+impl Display for Guess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.time, self.day, self.part, self.answer, self.verdict, self.wait_until
+        )
+    }
+}
+
+struct GuessHistory(Vec<Guess>);
+
+impl GuessHistory {
+    fn load() -> Result<Self> {
+        let history = match fs::read_to_string(history_path()) {
+            Ok(history) => history,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(GuessHistory(history.lines().filter_map(Guess::parse).collect()))
+    }
+
+    fn append(guess: &Guess) -> Result<()> {
+        let path = history_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{guess}")
+    }
+
+    /// Refuses submissions which are known to be pointless.
+    fn check(&self, day: u32, part: u32, answer: &str, now: u64) -> Result<()> {
+        if let Some(wait_until) = self.0.iter().filter(|g| g.day == day).map(|g| g.wait_until).max()
+            && wait_until > now
+        {
+            return Err(Error::other(format!(
+                "rate limited, wait {}s before submitting",
+                wait_until - now
+            )));
+        }
+
+        let guesses = self.0.iter().filter(|g| g.day == day && g.part == part);
+        for guess in guesses {
+            let refusal = match guess.verdict {
+                Verdict::Correct => Some(format!("already solved with {}", guess.answer)),
+                v if v.is_wrong() && guess.answer == answer => Some(format!("{answer} was already rejected as {v}")),
+                Verdict::TooHigh if compare(answer, &guess.answer) == Some(Ordering::Greater) => {
+                    Some(format!("{answer} is above {} which was too high", guess.answer))
+                }
+                Verdict::TooLow if compare(answer, &guess.answer) == Some(Ordering::Less) => {
+                    Some(format!("{answer} is below {} which was too low", guess.answer))
+                }
+                _ => None,
+            };
+            if let Some(refusal) = refusal {
+                return Err(Error::other(format!("refusing to submit: {refusal}")));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares unsigned decimal answers of any length, `None` if either is not a number.
+fn compare(a: &str, b: &str) -> Option<Ordering> {
+    let is_num = |s: &str| !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit());
+    if !is_num(a) || !is_num(b) {
+        return None;
+    }
+
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    Some(a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(part: u32, answer: &str, verdict: Verdict) -> Guess {
+        Guess {
+            time: 0,
+            day: 12,
+            part,
+            answer: answer.to_owned(),
+            verdict,
+            wait_until: 0,
+        }
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer.", Verdict::Correct),
+            ("You gave an answer too recently; you have to wait.", Verdict::RateLimited),
+            ("That's not the right answer; your answer is too high.", Verdict::TooHigh),
+            ("That's not the right answer; your answer is too low.", Verdict::TooLow),
+            ("That's not the right answer.", Verdict::Wrong),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Verdict::WrongLevel,
+            ),
+            ("<html>something else</html>", Verdict::Unknown),
+        ];
+        for (page, verdict) in cases {
+            assert_eq!(parse_response(page).0, verdict, "{page}");
+        }
+    }
+
+    #[test]
+    fn parses_waits() {
+        assert_eq!(parse_wait("you have 1m 5s left to wait."), Some(65));
+        assert_eq!(parse_wait("you have 2h 0m 3s left to wait."), Some(7203));
+        assert_eq!(parse_wait("please wait one minute before trying again."), Some(60));
+        assert_eq!(parse_wait("please wait 5 minutes before trying again."), Some(300));
+        assert_eq!(parse_wait("please wait 30 seconds."), Some(30));
+        assert_eq!(parse_wait("that's the right answer!"), None);
+
+        let (verdict, wait) = parse_response("You gave an answer too recently; you have 42s left to wait.");
+        assert_eq!((verdict, wait), (Verdict::RateLimited, Some(42)));
+    }
+
+    #[test]
+    fn compares_numbers_of_any_length() {
+        assert_eq!(compare("10", "9"), Some(Ordering::Greater));
+        assert_eq!(compare("0009", "10"), Some(Ordering::Less));
+        assert_eq!(compare("123", "123"), Some(Ordering::Equal));
+        assert_eq!(
+            compare("100000000000000000000000000000", "99999999999999999999999999999"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare("-1", "2"), None);
+        assert_eq!(compare("abc", "2"), None);
+        assert_eq!(compare("", "2"), None);
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers_and_bounds() {
+        let history = GuessHistory(vec![
+            guess(1, "50", Verdict::TooHigh),
+            guess(1, "10", Verdict::TooLow),
+            guess(1, "30", Verdict::Wrong),
+        ]);
+        assert!(history.check(12, 1, "30", 0).is_err());
+        assert!(history.check(12, 1, "60", 0).is_err());
+        assert!(history.check(12, 1, "5", 0).is_err());
+        assert!(history.check(12, 1, "20", 0).is_ok());
+        assert!(history.check(12, 2, "60", 0).is_ok());
+        assert!(history.check(13, 1, "30", 0).is_ok());
+    }
+
+    #[test]
+    fn refuses_solved_parts() {
+        let history = GuessHistory(vec![guess(1, "7", Verdict::Correct)]);
+        assert!(history.check(12, 1, "8", 0).is_err());
+        assert!(history.check(12, 2, "8", 0).is_ok());
+    }
+
+    #[test]
+    fn wrong_level_does_not_lock_the_part() {
+        let history = GuessHistory(vec![guess(2, "5", Verdict::WrongLevel), guess(1, "7", Verdict::Correct)]);
+        assert!(history.check(12, 2, "8", 0).is_ok());
+        assert!(history.check(12, 2, "5", 0).is_ok());
+    }
+
+    #[test]
+    fn refuses_while_rate_limited() {
+        let mut limited = guess(1, "5", Verdict::RateLimited);
+        limited.wait_until = 100;
+        let history = GuessHistory(vec![limited]);
+        assert!(history.check(12, 2, "8", 99).is_err());
+        assert!(history.check(12, 2, "8", 100).is_ok());
+    }
+
+    #[test]
+    fn submits_to_stub_and_records_guesses() {
+        use crate::stub::testing::{Scratch, start};
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        let scratch = Scratch::new("submit");
+        fs::write(scratch.dir.join("day7.answers"), "1698\n").unwrap();
+        let config = start(&scratch.dir, Duration::from_secs(1));
+
+        submit(&config, 7, 1, Some("1000")).unwrap();
+        let history = GuessHistory::load().unwrap();
+        let [low] = &history.0[..] else { panic!("expected one guess") };
+        assert_eq!((low.day, low.part, low.answer.as_str(), low.verdict), (7, 1, "1000", Verdict::TooLow));
+        assert!(low.wait_until > low.time, "wait of the stub is recorded");
+
+        // the recorded wait is enforced without asking the server
+        assert!(submit(&config, 7, 1, Some("1698")).unwrap_err().to_string().contains("rate limited"));
+
+        sleep(Duration::from_secs(2));
+        submit(&config, 7, 1, Some("1698")).unwrap();
+        let history = GuessHistory::load().unwrap();
+        assert_eq!(history.0.len(), 2);
+        assert_eq!(history.0[1].verdict, Verdict::Correct);
+        assert!(submit(&config, 7, 1, Some("1698")).unwrap_err().to_string().contains("already solved"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::io::{BufRead, Cursor, Read, Result};
+use std::io::{BufRead, Cursor, Error, Read, Result};
 use util::TaskInput;
 
 fn main() -> Result<()> {
@@ -12,67 +12,266 @@ impl util::Task for Task {
     type Output = usize;
 
     fn solve_1(input: Self::Input) -> Self::Output {
-        let rotations = input.0;
+        let dial = input.dial;
 
-        let mut pos = 50;
+        let mut trace = Trace::from_options(dial);
+
+        let mut pos = dial.relative_start();
         let mut res = 0usize;
-        for rot in rotations {
-            pos += rot;
-            pos = pos % 100;
+        for rot in input.rotations {
+            let old_pos = pos;
+            pos = (pos + rot as i64).rem_euclid(dial.size);
 
-            if pos == 0 {
-                res += 1;
-            }
+            let landed = (pos == 0) as usize;
+            res += landed;
+            trace.record(rot, old_pos, pos, landed, res);
         }
 
         res
     }
 
     fn solve_2(input: Self::Input) -> Self::Output {
-        let rotations = input.0;
+        let dial = input.dial;
+        let step = match input.variant {
+            Variant::Arith => rotate,
+            Variant::Sim => simulate,
+            Variant::Check => {
+                check_against_simulation(dial.size);
+                rotate
+            }
+        };
+
+        let mut trace = Trace::from_options(dial);
 
-        let mut pos = 50;
+        let mut pos = dial.relative_start();
         let mut res = 0usize;
-        for rot in rotations {
-            let old_pos = pos;
+        for rot in input.rotations {
+            let (next_pos, passes) = step(pos, rot as i64, dial.size);
+            res += passes;
+            trace.record(rot, pos, next_pos, passes, res);
+            pos = next_pos;
+        }
+
+        res
+    }
+}
+
+/// Rotates the dial and counts how often it points at 0 on the way, the
+/// starting position excluded. Returns the new position and the count.
+fn rotate(pos: i64, rot: i64, size: i64) -> (i64, usize) {
+    let old_pos = pos;
+
+    // считаем гарантированные полные обороты
+    let mut full_rotations = (rot.unsigned_abs() / size as u64) as usize;
+    // убираем уже посчитанные полные обороты
+    let new_rot = rot % size;
+
+    let next_pos = (pos + new_rot).rem_euclid(size);
+    // landing on 0 after whole turns only was already counted by them
+    if (old_pos != 0 && ((new_rot > 0 && old_pos > next_pos) || (new_rot < 0 && old_pos < next_pos)))
+        || (next_pos == 0 && new_rot != 0)
+    {
+        full_rotations += 1;
+    }
+
+    (next_pos, full_rotations)
+}
+
+/// Same as [`rotate`], but moves the dial one click at a time.
+fn simulate(mut pos: i64, rot: i64, size: i64) -> (i64, usize) {
+    let mut passes = 0usize;
+    for _ in 0..rot.abs() {
+        pos = (pos + rot.signum()).rem_euclid(size);
+        if pos == 0 {
+            passes += 1;
+        }
+    }
 
-            // считаем гарантированные полные обороты
-            let mut full_rotations = (rot.abs() as usize) / 100usize;
-            // убираем уже посчитанные полные обороты
-            let new_rot = rot % 100;
-
-            let next_pos = (pos + new_rot) % 100;
-            let next_pos = if next_pos < 0 {
-                100 + next_pos
-            } else {
-                next_pos
-            };
-            if old_pos != 0 && ((new_rot > 0 && old_pos > next_pos) || (new_rot < 0 && old_pos < next_pos)) {
-                full_rotations += 1;
-            } else if next_pos == 0 {
-                full_rotations += 1;
+    (pos, passes)
+}
+
+/// Compares [`rotate`] with [`simulate`] for every start position and every
+/// rotation in `-1000..=1000`.
+fn check_against_simulation(size: i64) {
+    for pos in 0..size {
+        for rot in -1000..=1000 {
+            let expected = simulate(pos, rot, size);
+            let actual = rotate(pos, rot, size);
+            assert_eq!(
+                actual, expected,
+                "rotating dial of size {size} at {pos} by {rot}: got (pos, passes) {actual:?}, simulation gives {expected:?}"
+            );
+        }
+    }
+}
+
+/// Per-rotation debug output on stderr, enabled with `--trace [text|csv]`.
+enum Trace {
+    Off,
+    Text(Dial),
+    Csv(Dial),
+}
+
+impl Trace {
+    fn from_options(dial: Dial) -> Self {
+        let options = util::options();
+        if !options.flag("trace") {
+            return Trace::Off;
+        }
+
+        match options.get("trace") {
+            None | Some("text") => Trace::Text(dial),
+            Some("csv") => {
+                eprintln!("rotation,start,end,passes,total");
+                Trace::Csv(dial)
             }
-            //println!("{old_pos} -> {rot} = {next_pos}; full {full_rotations}");
-            pos = next_pos;
+            Some(format) => panic!("unknown trace format {format}, expected text or csv"),
+        }
+    }
 
-            res += full_rotations;
+    /// Positions are relative to the target, they are printed as on the dial.
+    fn record(&mut self, rot: i32, start: i64, end: i64, passes: usize, total: usize) {
+        let (dial, csv) = match self {
+            Trace::Off => return,
+            Trace::Text(dial) => (dial, false),
+            Trace::Csv(dial) => (dial, true),
+        };
+        let start = dial.absolute(start);
+        let end = dial.absolute(end);
+        let rot = if rot < 0 { format!("L{}", -rot) } else { format!("R{rot}") };
+
+        if csv {
+            eprintln!("{rot},{start},{end},{passes},{total}");
+        } else {
+            eprintln!("{rot:>6}: {start:>4} -> {end:>4}  passes {passes:>3}  total {total}");
         }
+    }
+}
 
-        res
+/// Dial with `size` positions, pointing at `start` before the first rotation.
+///
+/// Both parts count how often the dial points at `target`. The solvers work
+/// with positions relative to the target, so the target is always at 0.
+#[derive(Clone, Copy, Debug)]
+struct Dial {
+    size: i64,
+    start: i64,
+    target: i64,
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Dial {
+            size: 100,
+            start: 50,
+            target: 0,
+        }
     }
 }
 
-struct Rotations(Vec<i32>);
+impl Dial {
+    fn relative_start(&self) -> i64 {
+        (self.start - self.target).rem_euclid(self.size)
+    }
+
+    fn absolute(&self, relative: i64) -> i64 {
+        (relative + self.target).rem_euclid(self.size)
+    }
+
+    /// Parses a `dial size=100 start=50 target=0` header, every key is optional.
+    fn parse_header(mut self, line: &str) -> Result<Self> {
+        for field in line.split_whitespace().skip(1) {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| Error::other(format!("expected key=value in dial header, got {field}")))?;
+            let value = value
+                .parse()
+                .map_err(|_| Error::other(format!("invalid dial {key}: {value}")))?;
+            match key {
+                "size" => self.size = value,
+                "start" => self.start = value,
+                "target" => self.target = value,
+                _ => return Err(Error::other(format!("unknown dial setting {key}"))),
+            }
+        }
+
+        Ok(self)
+    }
+
+    fn apply_options(mut self, options: &util::Options) -> Result<Self> {
+        self.size = options.parse_value("size")?.unwrap_or(self.size);
+        self.start = options.parse_value("start")?.unwrap_or(self.start);
+        self.target = options.parse_value("target")?.unwrap_or(self.target);
+        Ok(self)
+    }
+
+    fn validate(self) -> Result<Self> {
+        if self.size < 1 {
+            return Err(Error::other(format!("dial size must be positive, got {}", self.size)));
+        }
+        for (name, pos) in [("start", self.start), ("target", self.target)] {
+            if !(0..self.size).contains(&pos) {
+                return Err(Error::other(format!(
+                    "dial {name} {pos} is outside of 0..{}",
+                    self.size
+                )));
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// How part 2 turns the dial, `--variant arith|sim|check`.
+enum Variant {
+    Arith,
+    Sim,
+    Check,
+}
+
+impl Variant {
+    fn from_options(options: &util::Options) -> Result<Self> {
+        match options.get("variant") {
+            None | Some("arith") => Ok(Variant::Arith),
+            Some("sim") => Ok(Variant::Sim),
+            Some("check") => Ok(Variant::Check),
+            Some(variant) => Err(Error::other(format!(
+                "unknown variant {variant}, expected arith, sim or check"
+            ))),
+        }
+    }
+}
+
+struct Rotations {
+    dial: Dial,
+    variant: Variant,
+    rotations: Vec<i32>,
+}
 
 impl TaskInput for Rotations {
     fn read(mut input: impl Read) -> Result<Self> {
         let mut rotations = Vec::new();
         input.read_to_end(&mut rotations)?;
-        let rotations = Cursor::new(rotations)
-            .lines()
+        let mut lines = Cursor::new(rotations).lines().peekable();
+
+        let mut dial = Dial::default();
+        if let Some(Ok(header)) = lines.peek()
+            && header.starts_with("dial")
+        {
+            dial = dial.parse_header(header)?;
+            lines.next();
+        }
+        let dial = dial.apply_options(util::options())?.validate()?;
+        let variant = Variant::from_options(util::options())?;
+
+        let rotations = lines
             .map(|line| to_rotation(line.unwrap().as_str()))
             .collect();
-        Ok(Rotations(rotations))
+        Ok(Rotations {
+            dial,
+            variant,
+            rotations,
+        })
     }
 }
 
@@ -88,4 +287,37 @@ fn to_rotation(rot: &str) -> i32 {
             .unwrap(),
         _ => panic!("incorrect input: {rot}"),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_matches_simulation_on_default_dial() {
+        check_against_simulation(100);
+    }
+
+    #[test]
+    fn rotate_matches_simulation_on_tiny_dials() {
+        for size in 1..=7 {
+            check_against_simulation(size);
+        }
+    }
+
+    #[test]
+    fn rotate_matches_simulation_on_other_dials() {
+        for size in [10, 37, 64, 99, 101] {
+            check_against_simulation(size);
+        }
+    }
+
+    #[test]
+    fn rotate_counts_whole_turns_once() {
+        assert_eq!(rotate(0, 100, 100), (0, 1));
+        assert_eq!(rotate(0, -300, 100), (0, 3));
+        assert_eq!(rotate(50, 1000, 100), (50, 10));
+        assert_eq!(rotate(50, -50, 100), (0, 1));
+        assert_eq!(rotate(50, 0, 100), (50, 0));
+    }
+}
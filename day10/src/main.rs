@@ -25,9 +25,11 @@ impl util::Task for Task {
 
     fn solve_2(input: Self::Input) -> Self::Output {
         let mut res = 0usize;
-        for machine in input.0 {
-            res += count_joltage(&machine);
-            //println!("one done");
+        let progress = util::progress::start(input.0.len() as u64);
+        for (i, machine) in input.0.iter().enumerate() {
+            progress.message(format!("machine {i}"));
+            res += count_joltage(machine);
+            progress.inc(1);
         }
 
         res
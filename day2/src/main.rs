@@ -1,5 +1,7 @@
-use std::io::{BufRead, BufReader, Read, Result};
-use util::TaskInput;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Error, Read, Result};
+use std::num::IntErrorKind;
+use util::{BigUint, TaskInput};
 
 fn main() -> Result<()> {
     util::run::<Task>("day2/src/input.txt")
@@ -9,120 +11,416 @@ struct Task;
 
 impl util::Task for Task {
     type Input = Ranges;
-    type Output = usize;
+    type Output = BigUint;
 
     fn solve_1(input: Self::Input) -> Self::Output {
-        let ranges = input.0;
-        let mut res = 0usize;
+        let base = input.base;
+        if util::options().flag("explain") {
+            explain(base, &input.original, |len| (len % 2 == 0).then_some(len / 2).into_iter().collect());
+        }
 
-        for range in ranges {
-            let (start, end) = range;
+        input
+            .ranges
+            .into_iter()
+            .map(|(start, end)| count_halves(base, start, end))
+            .sum()
+    }
 
-            let mut id = start;
-            while id <= end {
-                let len = id.ilog10() + 1;
-                if len % 2 != 0 {
-                    id = 10usize.pow(len);
-                    continue;
-                }
+    fn solve_2(input: Self::Input) -> Self::Output {
+        let base = input.base;
+        if util::options().flag("explain") {
+            explain(base, &input.original, |len| (1..len).filter(|d| len % d == 0).collect());
+        }
+        let sum_range = match input.variant {
+            Variant::Count => count_repeated,
+            Variant::Scan => scan_repeated,
+        };
+
+        input
+            .ranges
+            .into_iter()
+            .map(|(start, end)| sum_range(base, start, end))
+            .sum()
+    }
+}
+
+/// Sums ids in `start..=end` made of a block repeated exactly twice.
+fn count_halves(base: u128, start: u128, end: u128) -> BigUint {
+    let mut res = BigUint::zero();
+    for len in (start.ilog(base) + 1)..=(end.ilog(base) + 1) {
+        if len.is_multiple_of(2) {
+            let (lo, hi) = clamp_to_len(base, len, start, end);
+            res += periodic_sum(base, len, len / 2, lo, hi);
+        }
+    }
+
+    res
+}
+
+/// Sums ids in `start..=end` made of a block repeated at least twice.
+///
+/// Works per id length `len`: `periodic_sum(d)` sums ids made of any `d` digit
+/// block, and an id with smallest block `p` is part of it for every `d`
+/// that `p` divides. Möbius inversion over the divisors of `len` leaves every
+/// id counted once, e.g. `111111` appears for `d` of 1, 2 and 3 and is
+/// counted as `periodic_sum(2) + periodic_sum(3) - periodic_sum(1)`.
+fn count_repeated(base: u128, start: u128, end: u128) -> BigUint {
+    let mut res = BigUint::zero();
+    for len in (start.ilog(base) + 1)..=(end.ilog(base) + 1) {
+        let (lo, hi) = clamp_to_len(base, len, start, end);
+
+        let mut added = BigUint::zero();
+        let mut removed = BigUint::zero();
+        for block_len in (1..len).filter(|d| len % d == 0) {
+            match mobius(len / block_len) {
+                -1 => added += periodic_sum(base, len, block_len, lo, hi),
+                1 => removed += periodic_sum(base, len, block_len, lo, hi),
+                _ => {}
+            }
+        }
+        res += added - removed;
+    }
+
+    res
+}
+
+/// The part of `start..=end` holding ids with `len` digits.
+fn clamp_to_len(base: u128, len: u32, start: u128, end: u128) -> (u128, u128) {
+    let lo = start.max(base.pow(len - 1));
+    let hi = end.min(base.checked_pow(len).map_or(u128::MAX, |next| next - 1));
+    (lo, hi)
+}
+
+/// Sums `len` digit ids in `lo..=hi` which are a `block_len` digit block repeated.
+fn periodic_sum(base: u128, len: u32, block_len: u32, lo: u128, hi: u128) -> BigUint {
+    let block_mask = mask(base, block_len);
+    // id = block * repeat, where repeat is 1 followed by (len / block_len - 1) blocks of 0..01
+    let Some(repeat) = repeat(block_mask, len / block_len) else {
+        return BigUint::zero();
+    };
+
+    let first = (block_mask / base).max(lo.div_ceil(repeat));
+    let last = (block_mask - 1).min(hi / repeat);
+    if first > last {
+        return BigUint::zero();
+    }
 
-                let part_len = len / 2;
-                let mask = mask(part_len);
-                let (_right, mut left) = parts(id, part_len);
-                let mut new_id = left * mask + left;
-                while new_id <= end {
-                    //print!("{id}: {left} - {right}; {new_id} ");
-                    if new_id >= start && left < mask {
-                        //print!("- hit!");
-                        res += new_id;
-                    }
-
-                    //println!();
-                    left += 1;
-                    new_id = left * mask + left;
+    // blocks have at most half of the digits, so these can not overflow
+    let (mut sum, mut count) = (first + last, last - first + 1);
+    if sum % 2 == 0 {
+        sum /= 2;
+    } else {
+        count /= 2;
+    }
+    BigUint::from(repeat) * BigUint::from(sum) * BigUint::from(count)
+}
+
+/// Multiplier turning a block into an id of `repeats` copies of it, `None` if
+/// it does not fit into 128 bits, in which case no such id does either.
+fn repeat(block_mask: u128, repeats: u32) -> Option<u128> {
+    (0..repeats).try_fold(0u128, |acc, _| acc.checked_mul(block_mask)?.checked_add(1))
+}
+
+fn mobius(mut n: u32) -> i32 {
+    let mut res = 1;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            res = -res;
+        }
+        p += 1;
+    }
+    if n > 1 {
+        res = -res;
+    }
+    res
+}
+
+/// Reference for [`count_repeated`] checking every id on its own.
+fn scan_repeated(base: u128, start: u128, end: u128) -> BigUint {
+    let mut res = BigUint::zero();
+    for id in start..=end {
+        if util::cancel::is_cancelled() {
+            return res;
+        }
+
+        let len = id.ilog(base) + 1;
+        let max_part_len = len / 2;
+
+        for part_len in 1..=max_part_len {
+            if len % part_len != 0 {
+                continue;
+            }
+
+            let mut matches = true;
+            let part_count = len / part_len;
+            let (mut right, mut left) = parts(base, id, part_len);
+
+            for _ in 0..part_count - 1 {
+                let old_right = right;
+                (right, left) = parts(base, left, part_len);
+                if old_right != right {
+                    matches = false;
+                    break;
                 }
+            }
+
+            if right == 0 {
+                continue;
+            }
 
-                id = new_id;
+            //print!("{start}-{end}: {id}; {right}");
+            if matches {
+                res += BigUint::from(id);
+                //println!(" - hit!");
+                break;
+            } else {
+                // println!();
             }
         }
-        res
     }
 
-    fn solve_2(input: Self::Input) -> Self::Output {
-        let ranges = input.0;
-        let mut res = 0usize;
-
-        for range in ranges {
-            let (start, end) = range;
-
-            for id in start..=end {
-                let len = id.ilog10() + 1;
-                let max_part_len = len / 2;
-
-                for part_len in 1..=max_part_len {
-                    if len % part_len != 0 {
-                        continue;
-                    }
-
-                    let mut matches = true;
-                    let part_count = len / part_len;
-                    let (mut right, mut left) = parts(id, part_len);
-
-                    for _ in 0..part_count - 1 {
-                        let old_right = right;
-                        (right, left) = parts(left, part_len);
-                        if old_right != right {
-                            matches = false;
-                            break;
-                        }
-                    }
-
-                    if right == 0 {
-                        continue;
-                    }
-
-                    //print!("{start}-{end}: {id}; {right}");
-                    if matches {
-                        res += id;
-                        //println!(" - hit!");
-                        break;
-                    } else {
-                        // println!();
-                    }
+    res
+}
+
+/// Lists the invalid ids of every input range on stderr with the block they
+/// repeat. Ranges are listed as given, before merging, so an id in
+/// overlapping ranges is listed for each of them.
+///
+/// `block_lens` gives the allowed block lengths for an id length; an id is
+/// reported with its shortest block.
+fn explain(base: u128, ranges: &[(u128, u128)], block_lens: impl Fn(u32) -> Vec<u32>) {
+    for &(start, end) in ranges {
+        let mut hits = BTreeMap::new();
+        // id 0 can not be invalid and has no length
+        let lens = (end > 0).then(|| (start.max(1).ilog(base) + 1)..=(end.ilog(base) + 1));
+        for len in lens.into_iter().flatten() {
+            for block_len in block_lens(len) {
+                let block_mask = mask(base, block_len);
+                let repeats = len / block_len;
+                let Some(repeat) = repeat(block_mask, repeats) else {
+                    continue;
+                };
+                let first = (block_mask / base).max(start.div_ceil(repeat));
+                let last = (block_mask - 1).min(end / repeat);
+                for block in first..=last {
+                    hits.entry(block * repeat).or_insert((block, repeats));
                 }
             }
         }
-        res
+
+        let range = format!("{}-{}", to_radix(start, base), to_radix(end, base));
+        if hits.is_empty() {
+            eprintln!("{range}: no invalid ids");
+            continue;
+        }
+        eprintln!("{range}: {} invalid ids", hits.len());
+        for (id, (block, repeats)) in hits {
+            eprintln!("  {} = {} x {repeats}", to_radix(id, base), to_radix(block, base));
+        }
     }
 }
 
-const fn mask(len: u32) -> usize {
-    10usize.pow(len)
+fn to_radix(mut num: u128, base: u128) -> String {
+    let mut digits = vec![];
+    loop {
+        digits.push(char::from_digit((num % base) as u32, base as u32).unwrap());
+        num /= base;
+        if num == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
 }
 
-const fn parts(id: usize, len: u32) -> (usize, usize) {
-    let mask = mask(len);
+/// Size of a block of `len` digits, i.e. `base^len`.
+const fn mask(base: u128, len: u32) -> u128 {
+    base.pow(len)
+}
+
+/// Splits `id` into its lowest `len` digits and the rest.
+const fn parts(base: u128, id: u128, len: u32) -> (u128, u128) {
+    let mask = mask(base, len);
     (id % mask, id / mask)
 }
 
-struct Ranges(Vec<(usize, usize)>);
+/// Comma separated `start-end` ranges of ids written in `base`, which is 10
+/// unless set by a leading `base N` line or `--base N`.
+///
+/// Overlapping and touching ranges are merged in `ranges`, so every id is
+/// counted once. `original` keeps the ranges as given for `--explain`.
+struct Ranges {
+    base: u128,
+    variant: Variant,
+    ranges: Vec<(u128, u128)>,
+    original: Vec<(u128, u128)>,
+}
+
+/// How part 2 sums the invalid ids, `--variant count|scan`.
+enum Variant {
+    Count,
+    Scan,
+}
+
+impl Variant {
+    fn from_options(options: &util::Options) -> Result<Self> {
+        match options.get("variant") {
+            None | Some("count") => Ok(Variant::Count),
+            Some("scan") => Ok(Variant::Scan),
+            Some(variant) => Err(Error::other(format!("unknown variant {variant}, expected count or scan"))),
+        }
+    }
+}
 
 impl TaskInput for Ranges {
     fn read(input: impl Read) -> Result<Self> {
-        let reader = BufReader::new(input);
-        let ranges = reader
-            .split(b',')
-            .map(|r| {
-                let line = r.unwrap();
-                let dash = line.iter().position(|&c| c == b'-').unwrap();
-                let (first, second) = line.split_at(dash);
-                let first = str::from_utf8(first).unwrap().trim().parse().unwrap();
-                let second = str::from_utf8(&second[1..]).unwrap().trim().parse().unwrap();
-
-                (first, second)
-            })
-            .collect();
-
-        Ok(Ranges(ranges))
+        let mut reader = BufReader::new(input);
+        let mut base = 10;
+
+        let mut header = String::new();
+        if reader.fill_buf()?.starts_with(b"base") {
+            reader.read_line(&mut header)?;
+            let value = header["base".len()..].trim();
+            base = value
+                .parse()
+                .map_err(|_| Error::other(format!("invalid base {value:?}")))?;
+        }
+        let base = util::options().parse_value("base")?.unwrap_or(base);
+        if !(2..=36).contains(&base) {
+            return Err(Error::other(format!("base must be in 2..=36, got {base}")));
+        }
+
+        let fields = reader.split(b',').collect::<Result<Vec<_>>>()?;
+        let mut ranges = Vec::with_capacity(fields.len());
+        for (i, field) in fields.iter().enumerate() {
+            let field = String::from_utf8_lossy(field);
+            let field = field.trim();
+            // a trailing comma or newline is fine
+            if field.is_empty() && i + 1 == fields.len() {
+                break;
+            }
+            ranges.push(parse_range(i + 1, field, base)?);
+        }
+
+        Ok(Ranges {
+            base: base as u128,
+            variant: Variant::from_options(util::options())?,
+            ranges: merge(ranges.clone()),
+            original: ranges,
+        })
+    }
+}
+
+fn parse_range(n: usize, field: &str, base: u32) -> Result<(u128, u128)> {
+    let err = |msg: String| Error::other(format!("range {n} {field:?}: {msg}"));
+    if field.is_empty() {
+        return Err(err("is empty".to_owned()));
+    }
+
+    let (start, end) = field
+        .split_once('-')
+        .ok_or_else(|| err("expected start-end".to_owned()))?;
+    let parse = |num: &str, name: &str| {
+        let num = num.trim();
+        if num.is_empty() {
+            return Err(err(format!("{name} is missing")));
+        }
+        u128::from_str_radix(num, base).map_err(|e| match e.kind() {
+            IntErrorKind::PosOverflow => err(format!("{name} does not fit into 128 bits")),
+            _ => err(format!("{name} {num:?} is not a base {base} number")),
+        })
+    };
+
+    let (start, end) = (parse(start, "start")?, parse(end, "end")?);
+    if end < start {
+        return Err(err("end is before start".to_owned()));
+    }
+
+    Ok((start, end))
+}
+
+/// Sorts ranges and merges overlapping or adjacent ones. Id 0 can not be
+/// invalid and is dropped, so every range starts at 1 or later.
+fn merge(mut ranges: Vec<(u128, u128)>) -> Vec<(u128, u128)> {
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        if end == 0 {
+            continue;
+        }
+        let start = start.max(1);
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASES: [u128; 5] = [2, 3, 7, 10, 36];
+
+    #[test]
+    fn count_halves_matches_brute_force() {
+        for base in BASES {
+            for start in 1..200 {
+                for end in (start..start + 300).step_by(7) {
+                    let expected = (start..=end)
+                        .filter(|&id| {
+                            let digits = to_radix(id, base);
+                            let (left, right) = digits.split_at(digits.len() / 2);
+                            digits.len().is_multiple_of(2) && left == right
+                        })
+                        .map(BigUint::from)
+                        .sum::<BigUint>();
+                    assert_eq!(count_halves(base, start, end), expected, "base {base}: {start}-{end}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn count_halves_handles_full_range() {
+        // 10 digit halves of the 20 digit ids, 11 digit halves are too large
+        let first = 1_000_000_000u128;
+        let last = 9_999_999_999u128;
+        let twenty_digits = BigUint::from(10_000_000_001u128) * BigUint::from((first + last) * (last - first + 1) / 2);
+        let all = count_halves(10, 1, u128::MAX);
+        assert!(all > twenty_digits);
+        assert_eq!(
+            count_halves(10, 10u128.pow(19), 10u128.pow(20) - 1),
+            twenty_digits
+        );
+    }
+
+    #[test]
+    fn count_repeated_matches_scan() {
+        for base in BASES {
+            for start in 1..200 {
+                for end in (start..start + 300).step_by(7) {
+                    assert_eq!(
+                        count_repeated(base, start, end),
+                        scan_repeated(base, start, end),
+                        "base {base}: {start}-{end}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn count_repeated_matches_scan_on_long_ids() {
+        for (start, end) in [(10u128.pow(11) - 5000, 10u128.pow(11) + 5000), (123_123_000, 123_124_000), (999_990, 1_000_010)] {
+            assert_eq!(count_repeated(10, start, end), scan_repeated(10, start, end), "{start}-{end}");
+        }
     }
 }
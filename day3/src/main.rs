@@ -1,6 +1,6 @@
-use std::io::{BufRead, BufReader, Read, Result};
-use util;
-use util::TaskInput;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, IsTerminal, Read, Result, stderr};
+use util::{BigUint, TaskInput};
 
 fn main() -> Result<()> {
     util::run::<Task>("day3/src/input.txt")
@@ -8,81 +8,237 @@ fn main() -> Result<()> {
 
 struct Task;
 
-impl util:: Task for Task {
+impl util::Task for Task {
     type Input = Banks;
-    type Output = usize;
+    type Output = BigUint;
 
     fn solve_1(input: Self::Input) -> Self::Output {
-        let banks = input.0;
-        let mut res = 0usize;
-        for bank in banks {
-            let left = 0usize;
-            let right = bank.len() - 1;
-            let left_possible = &bank[left..right];
-            let (left, left_val) = left_possible
-                .iter()
-                .copied()
-                .enumerate()
-                .rev()
-                .max_by_key(|(_, val)| *val)
-                .unwrap();
-            let right_possible = &bank[(left + 1)..];
-            let right_val = right_possible.iter().copied().max().unwrap();
-
-            res += (left_val as usize) * 10 + (right_val as usize);
+        if let Some(file) = input.stream {
+            return stream(file, input.k).0;
         }
-
-        res
+        let k = input.k.unwrap_or(2);
+        total_joltage(&input.banks, k, input.report)
     }
 
     fn solve_2(input: Self::Input) -> Self::Output {
-        let banks = input.0;
-        let mut res = 0usize;
-        for bank in banks {
-            let bank_len = bank.len();
-            let mut positions = [
-                -1i32,
-                (bank_len - 12) as i32,
-                (bank_len - 11) as i32,
-                (bank_len - 10) as i32,
-                (bank_len - 9) as i32,
-                (bank_len - 8) as i32,
-                (bank_len - 7) as i32,
-                (bank_len - 6) as i32,
-                (bank_len - 5) as i32,
-                (bank_len - 4) as i32,
-                (bank_len - 3) as i32,
-                (bank_len - 2) as i32,
-                (bank_len - 1) as i32,
-            ];
-            let mut values = [0usize; 12];
-            for i in 1..positions.len() {
-                let start = positions[i - 1];
-                let start = (start + 1) as usize;
-                let end = &mut positions[i];
-                let search_space = &bank[start..=(*end as usize)];
-                let (new_end, val) = search_space
-                    .iter()
-                    .copied()
-                    .enumerate()
-                    .rev()
-                    .max_by_key(|(_, val)| *val)
-                    .unwrap();
-                *end = (start + new_end) as i32;
-                values[i - 1] = val as usize;
-            }
+        if let Some(file) = input.stream {
+            return stream(file, input.k).1;
+        }
+        let k = input.k.unwrap_or(12);
+        total_joltage(&input.banks, k, input.report)
+    }
+}
+
+fn total_joltage(banks: &[Vec<u8>], k: usize, report: Option<Report>) -> BigUint {
+    let mut res = BigUint::zero();
+    let mut rows = vec![];
+    for (i, bank) in banks.iter().enumerate() {
+        let positions = max_subsequence(bank, k);
+        res += bank_joltage(i + 1, bank, k, positions.as_deref());
 
-            res += values.iter().fold(0usize, |acc, x| acc * 10 + *x);
+        if report.is_some() {
+            let runner_up = positions.as_ref().and_then(|p| runner_up(bank, p));
+            rows.push((bank, positions, runner_up));
         }
+    }
+
+    match report {
+        Some(Report::Table) => print_table(&rows),
+        Some(Report::Json) => print_json(&rows),
+        None => {}
+    }
+
+    res
+}
 
-        res
+/// Joltage of the bank `n` with the batteries at `positions` turned on, 0 with
+/// a warning if the bank had less than `k` batteries.
+fn bank_joltage(n: usize, bank: &[u8], k: usize, positions: Option<&[usize]>) -> BigUint {
+    match positions {
+        Some(positions) => joltage(bank, positions),
+        None => {
+            eprintln!("bank {n} has {} batteries, less than {k}, skipping", bank.len());
+            BigUint::zero()
+        }
     }
 }
 
-struct Banks(Vec<Vec<u8>>);
+/// Positions of the `k` batteries giving the largest joltage, `None` if the
+/// bank is shorter than `k`.
+///
+/// Greedy monotonic stack: a smaller digit is dropped whenever a larger one
+/// follows and there are still digits left to drop.
+fn max_subsequence(bank: &[u8], k: usize) -> Option<Vec<usize>> {
+    let mut to_drop = bank.len().checked_sub(k)?;
+    let mut stack: Vec<usize> = Vec::with_capacity(bank.len());
+    for (i, &digit) in bank.iter().enumerate() {
+        while to_drop > 0
+            && let Some(&top) = stack.last()
+            && bank[top] < digit
+        {
+            stack.pop();
+            to_drop -= 1;
+        }
+        stack.push(i);
+    }
+    stack.truncate(k);
+
+    Some(stack)
+}
+
+/// Best selection with a joltage lower than `best`, `None` if every selection
+/// gives the same joltage.
+///
+/// The runner-up keeps the longest possible prefix of `best` and then takes
+/// the largest smaller digit in the window of that step; the latest step where
+/// this is possible wins. `best` uses the leftmost positions for its digits,
+/// so every window is as wide as it can be.
+fn runner_up(bank: &[u8], best: &[usize]) -> Option<Vec<usize>> {
+    let k = best.len();
+    for step in (0..k).rev() {
+        let from = if step == 0 { 0 } else { best[step - 1] + 1 };
+        let to = bank.len() - (k - step);
+        let chosen = bank[best[step]];
+
+        let Some((pos, _)) = (from..=to)
+            .map(|i| (i, bank[i]))
+            .filter(|&(_, digit)| digit < chosen)
+            .min_by_key(|&(i, digit)| (std::cmp::Reverse(digit), i))
+        else {
+            continue;
+        };
+
+        let rest = max_subsequence(&bank[pos + 1..], k - step - 1)?;
+        let mut positions = best[..step].to_vec();
+        positions.push(pos);
+        positions.extend(rest.into_iter().map(|i| i + pos + 1));
+        return Some(positions);
+    }
+
+    None
+}
+
+type ReportRow<'a> = (&'a Vec<u8>, Option<Vec<usize>>, Option<Vec<usize>>);
+
+/// Per bank audit output on stderr, `--report [table|json]`.
+#[derive(Clone, Copy)]
+enum Report {
+    Table,
+    Json,
+}
+
+fn print_table(rows: &[ReportRow]) {
+    let list = |positions: &[usize]| positions.iter().map(usize::to_string).collect::<Vec<_>>().join(",");
+    let highlight = stderr().is_terminal();
+    for (i, (bank, best, runner_up)) in rows.iter().enumerate() {
+        let Some(best) = best else {
+            eprintln!("bank {:>4}  skipped, {} batteries", i + 1, bank.len());
+            continue;
+        };
+        eprintln!("bank {:>4}  joltage {}  positions {}", i + 1, joltage(bank, best), list(best));
+        match runner_up {
+            Some(runner_up) => eprintln!(
+                "           runner-up {}  positions {}",
+                joltage(bank, runner_up),
+                list(runner_up)
+            ),
+            None => eprintln!("           no runner-up"),
+        }
+
+        // chosen digits in color on a terminal, marked with carets below otherwise
+        let line = bank
+            .iter()
+            .enumerate()
+            .map(|(i, d)| match (best.contains(&i), highlight) {
+                (true, true) => format!("\x1b[1;32m{d}\x1b[0m"),
+                _ => d.to_string(),
+            })
+            .collect::<String>();
+        eprintln!("           {line}");
+        if !highlight {
+            let marks = (0..bank.len())
+                .map(|i| if best.contains(&i) { '^' } else { ' ' })
+                .collect::<String>();
+            eprintln!("           {}", marks.trim_end());
+        }
+    }
+}
+
+fn print_json(rows: &[ReportRow]) {
+    let selection = |bank: &[u8], positions: &Option<Vec<usize>>| match positions {
+        Some(positions) => format!(
+            "{{\"joltage\": {}, \"positions\": {:?}}}",
+            joltage(bank, positions),
+            positions
+        ),
+        None => "null".to_owned(),
+    };
+
+    eprintln!("[");
+    for (i, (bank, best, runner_up)) in rows.iter().enumerate() {
+        let marked = bank
+            .iter()
+            .enumerate()
+            .map(|(i, d)| match best {
+                Some(best) if best.contains(&i) => format!("[{d}]"),
+                _ => d.to_string(),
+            })
+            .collect::<String>();
+        eprintln!(
+            "  {{\"bank\": {}, \"best\": {}, \"runner_up\": {}, \"marked\": \"{marked}\"}}{}",
+            i + 1,
+            selection(bank, best),
+            selection(bank, runner_up),
+            if i + 1 < rows.len() { "," } else { "" }
+        );
+    }
+    eprintln!("]");
+}
+
+/// Digits at `positions` read as one number, which does not fit into a `u128`
+/// for more than 38 batteries.
+fn joltage(bank: &[u8], positions: &[usize]) -> BigUint {
+    let mut res = BigUint::zero();
+    for &i in positions {
+        res *= BigUint::from(10u8);
+        res += BigUint::from(bank[i]);
+    }
+    res
+}
+
+/// Batteries of every bank, `k` is how many of them to turn on if set with `--k`.
+///
+/// With `--stream` no banks are read up front, the input file is kept open in
+/// `stream` and read one bank at a time by the solver.
+struct Banks {
+    banks: Vec<Vec<u8>>,
+    k: Option<usize>,
+    report: Option<Report>,
+    stream: Option<File>,
+}
+
+impl Banks {
+    /// `--k` and `--report`, shared by both ways of reading.
+    fn settings() -> Result<(Option<usize>, Option<Report>)> {
+        let options = util::options();
+        let k = options.parse_value("k")?;
+        if k == Some(0) {
+            return Err(Error::other("k must be positive"));
+        }
+
+        let report = match (options.flag("report"), options.get("report")) {
+            (false, _) => None,
+            (true, None | Some("table")) => Some(Report::Table),
+            (true, Some("json")) => Some(Report::Json),
+            (true, Some(format)) => return Err(Error::other(format!("unknown report format {format}"))),
+        };
+        Ok((k, report))
+    }
+}
 
 impl TaskInput for Banks {
     fn read(input: impl Read) -> Result<Self> {
+        let (k, report) = Self::settings()?;
         let mut res = vec![];
         for line in BufReader::new(input).lines() {
             let line = line?;
@@ -93,6 +249,68 @@ impl TaskInput for Banks {
             res.push(bank);
         }
 
-        Ok(Banks(res))
+        Ok(Banks {
+            banks: res,
+            k,
+            report,
+            stream: None,
+        })
+    }
+
+    fn read_from_file(input_path: &str) -> Result<Self> {
+        if !util::options().flag("stream") {
+            return Self::read(File::open(input_path)?);
+        }
+
+        let (k, report) = Self::settings()?;
+        if report.is_some() {
+            return Err(Error::other("--report needs every bank, it can not be used with --stream"));
+        }
+        Ok(Banks {
+            banks: vec![],
+            k,
+            report,
+            stream: Some(File::open(input_path)?),
+        })
     }
+}
+
+/// Totals of both parts, solving every bank as soon as its line is read so
+/// only one bank is kept in memory. Panics on unreadable input, like the
+/// other solvers do on malformed input.
+fn stream(input: impl Read, k: Option<usize>) -> (BigUint, BigUint) {
+    let (k1, k2) = (k.unwrap_or(2), k.unwrap_or(12));
+    let mut reader = BufReader::new(input);
+    let mut bank = vec![];
+    let (mut total_1, mut total_2) = (BigUint::zero(), BigUint::zero());
+    for n in 1.. {
+        if util::cancel::is_cancelled() {
+            break;
+        }
+        bank.clear();
+        let read = reader
+            .read_until(b'\n', &mut bank)
+            .unwrap_or_else(|err| panic!("failed to read bank {n}: {err}"));
+        if read == 0 {
+            break;
+        }
+        // same line endings as `lines()`, `\n` or `\r\n`
+        if bank.last() == Some(&b'\n') {
+            bank.pop();
+            if bank.last() == Some(&b'\r') {
+                bank.pop();
+            }
+        }
+        for digit in bank.iter_mut() {
+            if !digit.is_ascii_digit() {
+                panic!("bank {n}: {:?} is not a digit", *digit as char);
+            }
+            *digit -= b'0';
+        }
+
+        total_1 += bank_joltage(n, &bank, k1, max_subsequence(&bank, k1).as_deref());
+        total_2 += bank_joltage(n, &bank, k2, max_subsequence(&bank, k2).as_deref());
+    }
+
+    (total_1, total_2)
 }
\ No newline at end of file
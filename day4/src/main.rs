@@ -1,5 +1,8 @@
+use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
-use std::io::{BufRead, BufReader, Read, Result};
+use std::fs;
+use std::io::{BufRead, BufReader, Error, Read, Result};
+use std::path::Path;
 use util::TaskInput;
 
 fn main() -> Result<()> {
@@ -13,81 +16,722 @@ impl util::Task for Task {
     type Output = usize;
 
     fn solve_1(input: Self::Input) -> Self::Output {
-        let mut count = 0usize;
+        match input.variant {
+            Variant::Bytes => accessible_by_bytes(input),
+            _ => {
+                let floor = BitFloor::from(&input);
+                count_ones(&floor.accessible(&input.rule, &vec![true; floor.rows]))
+            }
+        }
+    }
+
+    fn solve_2(input: Self::Input) -> Self::Output {
+        match input.variant {
+            Variant::Worklist => remove_by_worklist(input),
+            Variant::Rescan => remove_by_rescan(input),
+            _ => remove_by_bits(input),
+        }
+    }
+}
+
+/// Implementation used for the part, `--variant`. Checked against the part
+/// when reading, `bits` or `bytes` for part 1 and `bits`, `worklist` or
+/// `rescan` for part 2.
+#[derive(Clone, Copy, PartialEq)]
+enum Variant {
+    Bits,
+    Bytes,
+    Worklist,
+    Rescan,
+}
+
+impl Variant {
+    fn from_options(options: &util::Options) -> Result<Self> {
+        match (options.part(), options.get("variant")) {
+            (_, None | Some("bits")) => Ok(Variant::Bits),
+            (Some(1), Some("bytes")) => Ok(Variant::Bytes),
+            (Some(1), Some(variant)) => Err(Error::other(format!(
+                "unknown variant {variant} for part 1, expected bits or bytes"
+            ))),
+            (_, Some("worklist")) => Ok(Variant::Worklist),
+            (_, Some("rescan")) => Ok(Variant::Rescan),
+            (_, Some(variant)) => Err(Error::other(format!(
+                "unknown variant {variant} for part 2, expected bits, worklist or rescan"
+            ))),
+        }
+    }
+}
+
+/// Reference for part 1 on [`BitFloor`], reading the stencil of every roll.
+fn accessible_by_bytes(input: Floor) -> usize {
+    let mut count = 0usize;
+    //println!("{input:?}");
+    for x in 0..input.cols {
+        for y in 0..input.rows {
+            if let Some(tile) = input.get(x, y)
+                && tile == 1
+            {
+                let rolls = read_mask((x, y), &input, &input.rule).map(usize::from).sum::<usize>();
+                if rolls < input.rule.threshold {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Removes every accessible roll at once until none are left, one wave at a
+/// time, see [`BitFloor`]. Only rows seeing a row changed by the last wave
+/// are checked again.
+fn remove_by_bits(input: Floor) -> usize {
+    let mut floor = BitFloor::from(&input);
+    let record = wants_waves();
+    let mut waves = vec![0u32; if record { input.map.len() } else { 0 }];
+    let mut dirty = vec![true; floor.rows];
+
+    let mut count = 0usize;
+    for wave in 1.. {
+        let accessible = floor.accessible(&input.rule, &dirty);
+        let removed = count_ones(&accessible);
+        if removed == 0 {
+            break;
+        }
+        count += removed;
+        for (row, accessible) in floor.bits.iter_mut().zip(&accessible) {
+            *row &= !accessible;
+        }
+
+        dirty.fill(false);
+        for (y, changed) in accessible.chunks(floor.words).enumerate() {
+            if changed.iter().all(|&word| word == 0) {
+                continue;
+            }
+            for &(_, dy) in &input.rule.stencil {
+                for seeing in input.rule.boundary.unfold(y, dy, floor.rows) {
+                    dirty[seeing] = true;
+                }
+            }
+        }
+
+        if record {
+            for y in 0..floor.rows {
+                for x in 0..floor.cols {
+                    if accessible[y * floor.words + x / 64] >> (x % 64) & 1 == 1 {
+                        waves[x + y * floor.cols] = wave;
+                    }
+                }
+            }
+        }
+    }
+
+    if record {
+        let left = Floor {
+            map: floor.to_map(),
+            ..input
+        };
+        report_waves(&left, waves);
+    }
+
+    count
+}
+
+/// Removes accessible rolls until none are left, returns how many were removed.
+///
+/// Every roll keeps the number of rolls in its stencil. Removing a roll only
+/// lowers the counts of the rolls which have it in their stencil, and those
+/// dropping below the threshold are queued. Counts never grow, so a queued
+/// roll stays accessible and the order of removals does not matter.
+///
+/// The queue is processed in waves: wave 1 holds the rolls accessible at the
+/// start and wave `w + 1` the rolls made accessible by removing wave `w`.
+fn remove_by_worklist(mut input: Floor) -> usize {
+    let Floor { cols, rows, .. } = input;
+    let rule = input.rule.clone();
+    let mut counts = vec![0usize; input.map.len()];
+    // wave a roll was queued in, 0 if it never was
+    let mut waves = vec![0u32; input.map.len()];
+    let mut queue = VecDeque::new();
+    for y in 0..rows {
+        for x in 0..cols {
+            if input.get(x, y) == Some(1) {
+                let i = x + y * cols;
+                counts[i] = read_mask((x, y), &input, &rule).map(usize::from).sum();
+                if counts[i] < rule.threshold {
+                    waves[i] = 1;
+                    queue.push_back((x, y));
+                }
+            }
+        }
+    }
+
+    let mut count = 0usize;
+    while let Some((x, y)) = queue.pop_front() {
+        input.set(x, y, 0);
+        count += 1;
+        let wave = waves[x + y * cols];
+
+        // the rolls seeing (x, y), at the opposite offsets unless the boundary folds
+        for &(x_offset, y_offset) in &rule.stencil {
+            for ny in rule.boundary.unfold(y, y_offset, rows) {
+                for nx in rule.boundary.unfold(x, x_offset, cols) {
+                    let i = nx + ny * cols;
+                    if input.get(nx, ny) != Some(1) || waves[i] != 0 {
+                        continue;
+                    }
+                    counts[i] -= 1;
+                    if counts[i] < rule.threshold {
+                        waves[i] = wave + 1;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+    }
+
+    if wants_waves() {
+        report_waves(&input, waves);
+    }
+
+    count
+}
+
+fn wants_waves() -> bool {
+    let options = util::options();
+    options.flag("waves") || options.flag("frames") || options.get("images").is_some()
+}
+
+/// Waves are only recorded by part 2 with the `bits` and `worklist` variants.
+fn check_wave_options(variant: Variant) -> Result<()> {
+    let options = util::options();
+    if !wants_waves() {
+        return Ok(());
+    }
+    if options.part() == Some(1) {
+        return Err(Error::other("--waves, --frames and --images need part 2"));
+    }
+    if variant == Variant::Rescan {
+        return Err(Error::other("--waves, --frames and --images are not supported by --variant rescan"));
+    }
+    Ok(())
+}
+
+fn report_waves(floor: &Floor, waves: Vec<u32>) {
+    let options = util::options();
+    let waves = Waves { floor, waves };
+    if options.flag("waves") {
+        waves.print_summary();
+    }
+    if options.flag("frames") {
+        waves.print_frames();
+    }
+    if let Some(dir) = options.get("images")
+        && let Err(err) = waves.write_images(Path::new(dir))
+    {
+        eprintln!("failed to write wave images to {dir}: {err}");
+    }
+}
+
+/// Waves in which the rolls were removed, for `--waves`, `--frames` and
+/// `--images <dir>`. `floor` holds the rolls left after the last wave.
+struct Waves<'a> {
+    floor: &'a Floor,
+    waves: Vec<u32>,
+}
+
+impl Waves<'_> {
+    fn last(&self) -> u32 {
+        self.waves.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Whether (x, y) holds a roll after `wave` waves.
+    fn is_roll(&self, x: usize, y: usize, wave: u32) -> bool {
+        let removed_in = self.waves[x + y * self.floor.cols];
+        self.floor.get(x, y) == Some(1) || removed_in > wave
+    }
+
+    fn print_summary(&self) {
+        let mut removed = vec![0usize; self.last() as usize + 1];
+        for &wave in &self.waves {
+            removed[wave as usize] += 1;
+        }
+        let mut left = self.floor.map.iter().filter(|&&tile| tile == 1).count();
+        left += removed[1..].iter().sum::<usize>();
+        for (wave, &removed) in removed.iter().enumerate().skip(1) {
+            left -= removed;
+            eprintln!("wave {wave:>4}: removed {removed:>6}, left {left}");
+        }
+    }
+
+    /// Floor before the first wave and after every wave, the rolls removed in
+    /// that wave are marked with `x`.
+    fn print_frames(&self) {
+        for wave in 0..=self.last() {
+            eprintln!("wave {wave}:");
+            let frame = Frame(self.floor, |x, y| {
+                let removed_in = self.waves[x + y * self.floor.cols];
+                match self.is_roll(x, y, wave) {
+                    true => '@',
+                    false if removed_in == wave && wave > 0 => 'x',
+                    false => '.',
+                }
+            });
+            eprintln!("{frame:?}");
+        }
+    }
+
+    /// Writes a PGM image for the floor before the first wave and after every
+    /// wave. Rolls still there are white, removed rolls get brighter the later
+    /// they were removed and empty tiles are black.
+    fn write_images(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        let last = self.last();
+        let (cols, rows) = (self.floor.cols, self.floor.rows);
+        for wave in 0..=last {
+            let mut image = format!("P5\n{cols} {rows}\n255\n").into_bytes();
+            for y in 0..rows {
+                for x in 0..cols {
+                    let removed_in = self.waves[x + y * cols];
+                    image.push(match self.is_roll(x, y, wave) {
+                        true => 255,
+                        false if removed_in == 0 => 0,
+                        false => (48 + 160 * removed_in / last) as u8,
+                    });
+                }
+            }
+            fs::write(dir.join(format!("wave_{wave:04}.pgm")), image)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reference for [`remove_by_worklist`] rescanning the whole floor until
+/// nothing changes.
+fn remove_by_rescan(mut input: Floor) -> usize {
+    let mut count = 0usize;
+    loop {
+        let old_count = count;
         //println!("{input:?}");
         for x in 0..input.cols {
             for y in 0..input.rows {
                 if let Some(tile) = input.get(x, y)
                     && tile == 1
                 {
-                    let neighborhood = read_mask((x, y), &input, MASK3);
-                    let rolls = (neighborhood.iter().copied().sum::<u8>() as usize) - 1usize;
-                    if rolls < 4 {
+                    let rolls = read_mask((x, y), &input, &input.rule).map(usize::from).sum::<usize>();
+                    if rolls < input.rule.threshold {
                         count += 1;
+                        input.set(x, y, 0);
+
                     }
                 }
             }
         }
-        count
+
+        if old_count == count {
+            break;
+        }
     }
+    count
+}
+
+fn read_mask<'a>(
+    origin: (usize, usize),
+    map: &'a Floor,
+    rule: &'a Rule,
+) -> impl Iterator<Item = u8> + 'a {
+    rule.stencil.iter().map(move |&(x_offset, y_offset)| {
+        let x = rule.boundary.fold(origin.0 as i32 + x_offset, map.cols);
+        let y = rule.boundary.fold(origin.1 as i32 + y_offset, map.rows);
+
+        match (x, y) {
+            (Some(x), Some(y)) => map.get(x, y),
+            _ => None,
+        }
+        .unwrap_or(rule.boundary.outside())
+    })
+}
 
-    fn solve_2(mut input: Self::Input) -> Self::Output {
-        let mut count = 0usize;
-        loop {
-            let old_count = count;
-            //println!("{input:?}");
-            for x in 0..input.cols {
-                for y in 0..input.rows {
-                    if let Some(tile) = input.get(x, y)
-                        && tile == 1
-                    {
-                        let neighborhood = read_mask((x, y), &input, MASK3);
-                        let rolls = (neighborhood.iter().copied().sum::<u8>() as usize) - 1usize;
-                        if rolls < 4 {
-                            count += 1;
-                            input.set(x, y, 0);
+/// A roll is accessible if less than `threshold` tiles of its `stencil` hold
+/// rolls. Offsets of the stencil are relative to the roll and never include it.
+#[derive(Clone, Debug)]
+struct Rule {
+    stencil: Vec<(i32, i32)>,
+    threshold: usize,
+    boundary: Boundary,
+}
 
-                        }
+/// What the stencil sees past the edges of the floor.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Boundary {
+    /// Empty tiles.
+    #[default]
+    Empty,
+    /// Rolls which are never removed.
+    Wall,
+    /// The opposite edge, as on a torus.
+    Wrap,
+    /// The floor mirrored at the edge tiles, `-1` sees `1`.
+    Reflect,
+}
+
+impl Boundary {
+    /// Tile seen at `c` on an axis of `len` tiles, `None` if it is outside.
+    fn fold(self, c: i32, len: usize) -> Option<usize> {
+        let len = len as i32;
+        if (0..len).contains(&c) {
+            return Some(c as usize);
+        }
+        match self {
+            Boundary::Empty | Boundary::Wall => None,
+            _ if len == 0 => None,
+            Boundary::Wrap => Some(c.rem_euclid(len) as usize),
+            Boundary::Reflect if len == 1 => Some(0),
+            Boundary::Reflect => {
+                let period = 2 * (len - 1);
+                let c = c.rem_euclid(period);
+                Some(if c < len { c } else { period - c } as usize)
+            }
+        }
+    }
+
+    /// Tiles of an axis of `len` tiles which see `c` at `offset`, i.e. those
+    /// `s` with `fold(s + offset) == c`.
+    fn unfold(self, c: usize, offset: i32, len: usize) -> Vec<usize> {
+        let (c, len) = (c as i32, len as i32);
+        let candidates = match self {
+            Boundary::Empty | Boundary::Wall => vec![c - offset],
+            Boundary::Wrap => vec![(c - offset).rem_euclid(len)],
+            Boundary::Reflect if len == 1 => vec![0],
+            Boundary::Reflect => {
+                // s + offset is c or its mirror image, up to whole periods
+                let period = 2 * (len - 1);
+                let mut res = vec![(c - offset).rem_euclid(period), (-c - offset).rem_euclid(period)];
+                res.dedup();
+                res
+            }
+        };
+
+        candidates
+            .into_iter()
+            .filter(|s| (0..len).contains(s))
+            .map(|s| s as usize)
+            .collect()
+    }
+
+    /// Tile seen outside of the floor when `fold` gives `None`.
+    fn outside(self) -> u8 {
+        (self == Boundary::Wall) as u8
+    }
+}
+
+fn parse_boundary(name: &str) -> Result<Boundary> {
+    match name {
+        "empty" => Ok(Boundary::Empty),
+        "wall" => Ok(Boundary::Wall),
+        "wrap" => Ok(Boundary::Wrap),
+        "reflect" => Ok(Boundary::Reflect),
+        _ => Err(Error::other(format!(
+            "unknown boundary {name}, expected empty, wall, wrap or reflect"
+        ))),
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Shape {
+    /// Every tile within `radius` steps, diagonals included.
+    Moore,
+    /// Every tile within `radius` steps, diagonals excluded.
+    VonNeumann,
+    Custom(Vec<(i32, i32)>),
+}
+
+/// Settings from a `rule neighbourhood=moore radius=1 threshold=4 boundary=empty`
+/// header or from `--neighbourhood`, `--radius`, `--stencil`, `--threshold`
+/// and `--boundary`.
+#[derive(Clone, Debug)]
+struct RuleSettings {
+    shape: Shape,
+    radius: i32,
+    threshold: usize,
+    boundary: Boundary,
+}
+
+impl Default for RuleSettings {
+    fn default() -> Self {
+        RuleSettings {
+            shape: Shape::Moore,
+            radius: 1,
+            threshold: 4,
+            boundary: Boundary::Empty,
+        }
+    }
+}
+
+impl RuleSettings {
+    /// Parses a `rule` header, every key is optional.
+    fn parse_header(mut self, line: &str) -> Result<Self> {
+        for field in line.split_whitespace().skip(1) {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| Error::other(format!("expected key=value in rule header, got {field}")))?;
+            match key {
+                "neighbourhood" => self.shape = parse_shape(value)?,
+                "stencil" => self.shape = parse_stencil(value)?,
+                "radius" => self.radius = parse_number(key, value)?,
+                "threshold" => self.threshold = parse_number(key, value)?,
+                "boundary" => self.boundary = parse_boundary(value)?,
+                _ => return Err(Error::other(format!("unknown rule setting {key}"))),
+            }
+        }
+
+        Ok(self)
+    }
+
+    fn apply_options(mut self, options: &util::Options) -> Result<Self> {
+        match (options.get("neighbourhood"), options.get("stencil")) {
+            (Some(_), Some(_)) => return Err(Error::other("use either --neighbourhood or --stencil")),
+            (Some(shape), None) => self.shape = parse_shape(shape)?,
+            (None, Some(stencil)) => self.shape = parse_stencil(stencil)?,
+            (None, None) => {}
+        }
+        self.radius = options.parse_value("radius")?.unwrap_or(self.radius);
+        self.threshold = options.parse_value("threshold")?.unwrap_or(self.threshold);
+        if let Some(boundary) = options.get("boundary") {
+            self.boundary = parse_boundary(boundary)?;
+        }
+        Ok(self)
+    }
+
+    fn into_rule(self) -> Result<Rule> {
+        let r = self.radius;
+        if r < 1 {
+            return Err(Error::other(format!("radius must be positive, got {r}")));
+        }
+        let around = || (-r..=r).flat_map(|y| (-r..=r).map(move |x| (x, y)));
+        let stencil = match self.shape {
+            Shape::Moore => around().filter(|&offset| offset != (0, 0)).collect(),
+            Shape::VonNeumann => around()
+                .filter(|&(x, y)| (x, y) != (0, 0) && x.abs() + y.abs() <= r)
+                .collect(),
+            Shape::Custom(stencil) => stencil,
+        };
+
+        Ok(Rule {
+            stencil,
+            threshold: self.threshold,
+            boundary: self.boundary,
+        })
+    }
+}
+
+fn parse_shape(name: &str) -> Result<Shape> {
+    match name {
+        "moore" => Ok(Shape::Moore),
+        "von-neumann" => Ok(Shape::VonNeumann),
+        _ => Err(Error::other(format!(
+            "unknown neighbourhood {name}, expected moore or von-neumann"
+        ))),
+    }
+}
+
+/// Parses a stencil drawn like the floor with rows separated by `/`, e.g.
+/// `.@./@.@/.@.` for the four direct neighbours. The center tile is the roll
+/// itself and is never counted.
+fn parse_stencil(drawing: &str) -> Result<Shape> {
+    let rows = drawing.split('/').collect::<Vec<_>>();
+    let height = rows.len();
+    let width = rows[0].len();
+    if height % 2 == 0 || width % 2 == 0 || rows.iter().any(|row| row.len() != width) {
+        return Err(Error::other(format!(
+            "stencil {drawing} must have the same odd number of tiles in every row and an odd number of rows"
+        )));
+    }
+
+    let (cx, cy) = ((width / 2) as i32, (height / 2) as i32);
+    let mut stencil = vec![];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let offset = (x as i32 - cx, y as i32 - cy);
+            match c {
+                '@' if offset != (0, 0) => stencil.push(offset),
+                '@' | '.' => {}
+                c => return Err(Error::other(format!("unexpected character '{c}' in stencil {drawing}"))),
+            }
+        }
+    }
+
+    Ok(Shape::Custom(stencil))
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| Error::other(format!("invalid rule {key}: {value}")))
+}
+
+/// [`Floor`] with one bit per tile, every row packed into `words` u64 words
+/// with the tile `x` at bit `x % 64` of word `x / 64`.
+///
+/// Neighbour counts are computed for 64 tiles at once: the row of every
+/// stencil offset is shifted so its tiles line up with their neighbour, and
+/// the shifted words are added into bit-sliced counters, where word `b` of a
+/// counter holds bit `b` of the count of every tile.
+struct BitFloor {
+    cols: usize,
+    rows: usize,
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl From<&Floor> for BitFloor {
+    fn from(floor: &Floor) -> Self {
+        let words = floor.cols.div_ceil(64);
+        let mut bits = vec![0u64; words * floor.rows];
+        for y in 0..floor.rows {
+            for x in 0..floor.cols {
+                if floor.get(x, y) == Some(1) {
+                    bits[y * words + x / 64] |= 1 << (x % 64);
+                }
+            }
+        }
+
+        BitFloor {
+            cols: floor.cols,
+            rows: floor.rows,
+            words,
+            bits,
+        }
+    }
+}
+
+impl BitFloor {
+    fn to_map(&self) -> Vec<u8> {
+        let mut map = vec![0u8; self.cols * self.rows];
+        for y in 0..self.rows {
+            for x in 0..self.cols {
+                map[x + y * self.cols] = (self.bits[y * self.words + x / 64] >> (x % 64) & 1) as u8;
+            }
+        }
+        map
+    }
+
+    fn tile(&self, x: usize, y: usize) -> bool {
+        self.bits[y * self.words + x / 64] >> (x % 64) & 1 == 1
+    }
+
+    /// Every row with `pad` tiles added on both sides as the boundary sees
+    /// them, so the stencil can be applied by shifting the padded row.
+    fn padded_rows(&self, pad: usize, boundary: Boundary) -> Vec<Vec<u64>> {
+        let words = (self.cols + 2 * pad).div_ceil(64);
+        let mut res = Vec::with_capacity(self.rows);
+        for y in 0..self.rows {
+            let row = &self.bits[y * self.words..(y + 1) * self.words];
+            let mut padded = (0..words)
+                .map(|w| shifted(row, w, -(pad as isize)))
+                .collect::<Vec<_>>();
+            for i in 0..pad {
+                let outside = [(i, -((pad - i) as i32)), (pad + self.cols + i, (self.cols + i) as i32)];
+                for (bit, x) in outside {
+                    let roll = match boundary.fold(x, self.cols) {
+                        Some(x) => self.tile(x, y),
+                        None => boundary.outside() == 1,
+                    };
+                    padded[bit / 64] |= (roll as u64) << (bit % 64);
+                }
+            }
+            res.push(padded);
+        }
+
+        res
+    }
+
+    /// Mask of the rolls with less than `rule.threshold` rolls in their
+    /// stencil, only rows marked in `rows` are checked.
+    fn accessible(&self, rule: &Rule, rows: &[bool]) -> Vec<u64> {
+        let pad = rule.stencil.iter().map(|&(dx, _)| dx.unsigned_abs() as usize).max().unwrap_or(0);
+        let padded = self.padded_rows(pad, rule.boundary);
+        let wall = vec![!0u64; padded.first().map_or(0, Vec::len)];
+
+        // enough counter bits for every tile of the stencil holding a roll
+        let planes = (usize::BITS - rule.stencil.len().leading_zeros()) as usize;
+        let mut counters = vec![0u64; planes * self.words];
+        let mut res = vec![0u64; self.bits.len()];
+        for y in (0..self.rows).filter(|&y| rows[y]) {
+            counters.fill(0);
+            for &(dx, dy) in &rule.stencil {
+                let row = match rule.boundary.fold(y as i32 + dy, self.rows) {
+                    Some(sy) => &padded[sy],
+                    None if rule.boundary.outside() == 1 => &wall,
+                    None => continue,
+                };
+                for w in 0..self.words {
+                    // ripple carry add of one bit to every counter
+                    let mut carry = shifted(row, w, pad as isize + dx as isize);
+                    for plane in 0..planes {
+                        let counter = &mut counters[w * planes + plane];
+                        (*counter, carry) = (*counter ^ carry, *counter & carry);
                     }
                 }
             }
 
-            if old_count == count {
-                break;
+            for w in 0..self.words {
+                let less = match rule.threshold > rule.stencil.len() {
+                    true => !0,
+                    false => less_than(&counters[w * planes..(w + 1) * planes], rule.threshold),
+                };
+                res[y * self.words + w] = self.bits[y * self.words + w] & less;
             }
         }
-        count
+
+        res
     }
 }
 
-fn read_mask<const N: usize>(
-    origin: (usize, usize),
-    map: &Floor,
-    mask: [(i32, i32); N],
-) -> [u8; N] {
-    mask.map(|(x_offset, y_offset)| {
-        let x = origin.0 as i32 + x_offset;
-        let y = origin.1 as i32 + y_offset;
-
-        if x < 0 || y < 0 || x as usize >= map.cols || y as usize >= map.rows {
-            None
+/// Word `w` of `row` moved by `shift` tiles, so that bit `x` holds the tile
+/// at `x + shift`. Tiles outside of the row are empty.
+fn shifted(row: &[u64], w: usize, shift: isize) -> u64 {
+    let word = |w: isize| {
+        usize::try_from(w)
+            .ok()
+            .and_then(|w| row.get(w))
+            .copied()
+            .unwrap_or(0)
+    };
+    let (words, bits) = (shift.div_euclid(64), shift.rem_euclid(64) as u32);
+    let low = word(w as isize + words);
+    if bits == 0 {
+        return low;
+    }
+    (low >> bits) | (word(w as isize + words + 1) << (64 - bits))
+}
+
+/// Mask of the bit-sliced counters, least significant bit first, which are
+/// less than `threshold`. `threshold` has to fit into the counters.
+fn less_than(planes: &[u64], threshold: usize) -> u64 {
+    let (mut less, mut equal) = (0u64, !0u64);
+    for (bit, &plane) in planes.iter().enumerate().rev() {
+        if threshold >> bit & 1 == 1 {
+            less |= equal & !plane;
+            equal &= plane;
         } else {
-            map.get(x as usize, y as usize)
+            equal &= !plane;
         }
-        .unwrap_or(0u8)
-    })
+    }
+
+    less
 }
 
-const MASK3: [(i32, i32); 9] = [
-    (-1, -1), (0, -1), (1, -1),
-    (-1, 0), (0, 0), (1, 0),
-    (-1, 1), (0, 1), (1, 1),
-];
+fn count_ones(words: &[u64]) -> usize {
+    words.iter().map(|word| word.count_ones() as usize).sum()
+}
 
 struct Floor {
     cols: usize,
     rows: usize,
     map: Vec<u8>,
+    rule: Rule,
+    variant: Variant,
 }
 
 impl Floor {
@@ -102,10 +746,19 @@ impl Floor {
 
 impl Debug for Floor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.rows {
-            for x in 0..self.cols {
-                let tile = self.get(x, y).unwrap();
-                write!(f, "{}", if tile == 0 { '.' } else { '@' })?;
+        let frame = Frame(self, |x, y| if self.get(x, y).unwrap() == 0 { '.' } else { '@' });
+        Debug::fmt(&frame, f)
+    }
+}
+
+/// Draws a character for every tile of the floor.
+struct Frame<'a, F>(&'a Floor, F);
+
+impl<F: Fn(usize, usize) -> char> Debug for Frame<'_, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.0.rows {
+            for x in 0..self.0.cols {
+                write!(f, "{}", (self.1)(x, y))?;
             }
             writeln!(f)?;
         }
@@ -116,8 +769,19 @@ impl Debug for Floor {
 
 impl TaskInput for Floor {
     fn read(input: impl Read) -> Result<Self> {
+        let mut reader = BufReader::new(input);
+        let mut settings = RuleSettings::default();
+        if reader.fill_buf()?.starts_with(b"rule") {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            settings = settings.parse_header(&header)?;
+        }
+        let rule = settings.apply_options(util::options())?.into_rule()?;
+        let variant = Variant::from_options(util::options())?;
+        check_wave_options(variant)?;
+
         let mut res = vec![];
-        let lines = BufReader::new(input).lines();
+        let lines = reader.lines();
         let mut rows = 0usize;
         let mut cols = 0usize;
         for line in lines {
@@ -140,6 +804,8 @@ impl TaskInput for Floor {
             cols,
             rows,
             map: res,
+            rule,
+            variant,
         })
     }
 }
@@ -1,9 +1,20 @@
-use std::io::{BufRead, BufReader, Read, Result};
+mod query;
+
+use std::env::args;
+use std::io::{BufRead, BufReader, Error, Read, Result, stdin, stdout};
 use std::ops::RangeInclusive;
 use util::TaskInput;
 
+const INPUT: &str = "day5/src/input.txt";
+
 fn main() -> Result<()> {
-    util::run::<Task>("day5/src/input.txt")
+    // `day5 query` answers freshness queries on stdin instead of solving
+    if args().nth(1).as_deref() == Some("query") {
+        let db = FreshDatabase::read_from_file(INPUT)?;
+        return query::serve(db, stdin().lock(), stdout().lock());
+    }
+
+    util::run::<Task>(INPUT)
 }
 
 struct Task;
@@ -13,48 +24,205 @@ impl util::Task for Task {
     type Output = usize;
 
     fn solve_1(input: Self::Input) -> Self::Output {
-        let count = input
-            .ingredients
-            .iter()
-            .filter(|ingredient| input.fresh.iter().any(|fresh| fresh.contains(*ingredient)))
-            .count();
-
-        count
+        if util::options().flag("explain") {
+            explain(&input);
+        }
+        match input.variant {
+            Variant::Search => {
+                let fresh = merge(input.fresh);
+                input
+                    .ingredients
+                    .iter()
+                    .filter(|&&ingredient| is_fresh(&fresh, ingredient))
+                    .count()
+            }
+            Variant::Sweep => sweep(merge(input.fresh), input.ingredients),
+            Variant::Naive => input
+                .ingredients
+                .iter()
+                .filter(|ingredient| input.fresh.iter().any(|fresh| fresh.contains(*ingredient)))
+                .count(),
+        }
     }
 
     fn solve_2(input: Self::Input) -> Self::Output {
-        let mut fresh_db = input.fresh;
-        fresh_db.sort_unstable_by_key(|fresh| *fresh.start());
-
-        let mut count = 0usize;
-
-        let mut prev: Option<RangeInclusive<usize>> = None;
-
-        for fresh in fresh_db {
-            if let Some(prev_fresh) = prev.clone() {
-                if fresh.start() > prev_fresh.end() {
-                    count += prev_fresh.count();
-                    prev = Some(fresh);
-                } else if fresh.end() > prev_fresh.end() {
-                    prev = Some(*prev_fresh.start()..=*fresh.end());
-                }
-            } else {
+        if util::options().flag("explain") {
+            explain(&input);
+        }
+        merge(input.fresh).into_iter().map(RangeInclusive::count).sum()
+    }
+}
+
+/// Sorts the ranges and merges overlapping ones, the result is sorted and disjoint.
+fn merge(mut fresh_db: Vec<RangeInclusive<usize>>) -> Vec<RangeInclusive<usize>> {
+    fresh_db.sort_unstable_by_key(|fresh| *fresh.start());
+
+    let mut merged = vec![];
+    let mut prev: Option<RangeInclusive<usize>> = None;
+
+    for fresh in fresh_db {
+        if let Some(prev_fresh) = prev.clone() {
+            if fresh.start() > prev_fresh.end() {
+                merged.push(prev_fresh);
                 prev = Some(fresh);
+            } else if fresh.end() > prev_fresh.end() {
+                prev = Some(*prev_fresh.start()..=*fresh.end());
+            }
+        } else {
+            prev = Some(fresh);
+        }
+    }
+
+    merged.extend(prev);
+    merged
+}
+
+/// Binary search in ranges as returned by [`merge`].
+fn is_fresh(merged: &[RangeInclusive<usize>], ingredient: usize) -> bool {
+    let i = merged.partition_point(|fresh| *fresh.end() < ingredient);
+    merged.get(i).is_some_and(|fresh| fresh.contains(&ingredient))
+}
+
+/// Counts fresh ingredients by walking the sorted ingredients and the ranges
+/// as returned by [`merge`] side by side.
+fn sweep(merged: Vec<RangeInclusive<usize>>, mut ingredients: Vec<usize>) -> usize {
+    ingredients.sort_unstable();
+
+    let mut count = 0usize;
+    let mut fresh = merged.iter().peekable();
+    for ingredient in ingredients {
+        while fresh.next_if(|fresh| *fresh.end() < ingredient).is_some() {}
+        match fresh.peek() {
+            Some(fresh) if fresh.contains(&ingredient) => count += 1,
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    count
+}
+
+/// Lists on stderr which ranges contain every ingredient, the ranges covered
+/// by the other ranges, the groups of overlapping ranges merged by part 2 and
+/// the largest gap between them. Ranges are named by their line in the input.
+fn explain(db: &FreshDatabase) {
+    let lines = |ranges: &mut dyn Iterator<Item = usize>| {
+        ranges.map(|i| (i + 1).to_string()).collect::<Vec<_>>().join(", ")
+    };
+    let show = |range: &RangeInclusive<usize>| format!("{}-{}", range.start(), range.end());
+
+    for &ingredient in &db.ingredients {
+        let mut containing = (0..db.fresh.len()).filter(|&i| db.fresh[i].contains(&ingredient)).peekable();
+        match containing.peek() {
+            Some(_) => eprintln!("{ingredient}: fresh, in ranges on lines {}", lines(&mut containing)),
+            None => eprintln!("{ingredient}: spoiled"),
+        }
+    }
+
+    let redundant = redundant(&db.fresh);
+    eprintln!("{} of {} ranges are covered by other ranges", redundant.len(), db.fresh.len());
+    for i in redundant {
+        eprintln!("  line {}: {}", i + 1, show(&db.fresh[i]));
+    }
+
+    let groups = overlap_groups(&db.fresh);
+    eprintln!("{} ranges after merging", groups.len());
+    for (merged, members) in groups.iter().filter(|(_, members)| members.len() > 1) {
+        eprintln!("  {} from lines {}", show(merged), lines(&mut members.iter().copied()));
+    }
+
+    let gap = groups
+        .windows(2)
+        .map(|pair| (pair[0].0.end() + 1)..=pair[1].0.start().saturating_sub(1))
+        .filter(|gap| !gap.is_empty())
+        .max_by_key(|gap| gap.end() - gap.start());
+    match gap {
+        Some(gap) => eprintln!("largest gap: {} ({} ids)", show(&gap), gap.end() - gap.start() + 1),
+        None => eprintln!("no gaps between the ranges"),
+    }
+}
+
+/// Indexes of the ranges whose ids are all in other ranges. Of two equal
+/// ranges both are reported, only one of them can be dropped.
+fn redundant(fresh: &[RangeInclusive<usize>]) -> Vec<usize> {
+    let mut res = vec![];
+    for (i, range) in fresh.iter().enumerate() {
+        let mut others = fresh
+            .iter()
+            .enumerate()
+            .filter(|&(j, other)| j != i && other.start() <= range.end() && other.end() >= range.start())
+            .map(|(_, other)| other)
+            .collect::<Vec<_>>();
+        others.sort_unstable_by_key(|other| *other.start());
+
+        // first id of the range not covered yet, u128 so it can pass usize::MAX
+        let mut uncovered = *range.start() as u128;
+        for other in others {
+            if *other.start() as u128 > uncovered {
+                break;
             }
+            uncovered = uncovered.max(*other.end() as u128 + 1);
+        }
+        if uncovered > *range.end() as u128 {
+            res.push(i);
         }
+    }
+
+    res
+}
+
+/// Same groups as [`merge`], with the indexes of the ranges in every group.
+fn overlap_groups(fresh: &[RangeInclusive<usize>]) -> Vec<(RangeInclusive<usize>, Vec<usize>)> {
+    let mut order = (0..fresh.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| *fresh[i].start());
 
-        count += prev.map(RangeInclusive::count).unwrap_or(0);
-        count
+    let mut groups: Vec<(RangeInclusive<usize>, Vec<usize>)> = vec![];
+    for i in order {
+        let range = &fresh[i];
+        match groups.last_mut() {
+            Some((merged, members)) if range.start() <= merged.end() => {
+                *merged = *merged.start()..=*merged.end().max(range.end());
+                members.push(i);
+            }
+            _ => groups.push((range.clone(), vec![i])),
+        }
+    }
+    for (_, members) in &mut groups {
+        members.sort_unstable();
     }
+
+    groups
 }
 
 struct FreshDatabase {
     fresh: Vec<RangeInclusive<usize>>,
     ingredients: Vec<usize>,
+    variant: Variant,
+}
+
+/// How part 1 looks up the ingredients, `--variant search|sweep|naive`.
+enum Variant {
+    Search,
+    Sweep,
+    Naive,
+}
+
+impl Variant {
+    fn from_options(options: &util::Options) -> Result<Self> {
+        match options.get("variant") {
+            None | Some("search") => Ok(Variant::Search),
+            Some("sweep") => Ok(Variant::Sweep),
+            Some("naive") => Ok(Variant::Naive),
+            Some(variant) => Err(Error::other(format!(
+                "unknown variant {variant}, expected search, sweep or naive"
+            ))),
+        }
+    }
 }
 
 impl TaskInput for FreshDatabase {
     fn read(input: impl Read) -> Result<Self> {
+        let variant = Variant::from_options(util::options())?;
         let reader = BufReader::new(input);
         let mut fresh = vec![];
         let mut ingredients = vec![];
@@ -82,6 +250,10 @@ impl TaskInput for FreshDatabase {
             ingredients.push(num);
         }
 
-        Ok(FreshDatabase { fresh, ingredients })
+        Ok(FreshDatabase {
+            fresh,
+            ingredients,
+            variant,
+        })
     }
 }
@@ -0,0 +1,171 @@
+use crate::FreshDatabase;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Error, Result, Write};
+use std::ops::RangeInclusive;
+
+/// Answers one command per line of `input` with one line on `output`:
+///
+/// - `is 17`: `fresh` or `spoiled`
+/// - `add 3-9`, `remove 5-6`: `ok`, changes the fresh ranges
+/// - `count`: number of fresh ids
+/// - `gaps 0-100`: ranges without fresh ids in `0..=100`, `none` if there are none
+///
+/// Invalid commands are answered with `error: ...` and do not end the session.
+pub fn serve(db: FreshDatabase, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    let mut fresh = FreshSet::default();
+    for range in db.fresh {
+        fresh.add(*range.start(), *range.end());
+    }
+
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match answer(&mut fresh, line) {
+            Ok(res) => writeln!(output, "{res}")?,
+            Err(err) => writeln!(output, "error: {err}")?,
+        }
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+fn answer(fresh: &mut FreshSet, line: &str) -> Result<String> {
+    let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+    let arg = arg.trim();
+    match command {
+        "is" => {
+            let id = parse_id(arg)?;
+            Ok(if fresh.contains(id) { "fresh" } else { "spoiled" }.to_owned())
+        }
+        "add" => {
+            let (start, end) = parse_range(arg)?;
+            fresh.add(start, end);
+            Ok("ok".to_owned())
+        }
+        "remove" => {
+            let (start, end) = parse_range(arg)?;
+            fresh.remove(start, end);
+            Ok("ok".to_owned())
+        }
+        "count" => Ok(fresh.count.to_string()),
+        "gaps" => {
+            let (start, end) = parse_range(arg)?;
+            let gaps = fresh.gaps(start, end);
+            if gaps.is_empty() {
+                return Ok("none".to_owned());
+            }
+            Ok(gaps
+                .iter()
+                .map(|gap| format!("{}-{}", gap.start(), gap.end()))
+                .collect::<Vec<_>>()
+                .join(" "))
+        }
+        _ => Err(Error::other(format!(
+            "unknown command {command:?}, expected is, add, remove, count or gaps"
+        ))),
+    }
+}
+
+fn parse_id(id: &str) -> Result<usize> {
+    id.parse()
+        .map_err(|_| Error::other(format!("invalid id {id:?}")))
+}
+
+fn parse_range(range: &str) -> Result<(usize, usize)> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| Error::other(format!("expected start-end, got {range:?}")))?;
+    let (start, end) = (parse_id(start.trim())?, parse_id(end.trim())?);
+    if end < start {
+        return Err(Error::other(format!("range {range} ends before it starts")));
+    }
+
+    Ok((start, end))
+}
+
+/// Fresh ids as disjoint ranges which do not touch, keyed by their start.
+/// `count` is the number of ids in all of them, `0-usize::MAX` alone does not fit a `usize`.
+#[derive(Default)]
+struct FreshSet {
+    ranges: BTreeMap<usize, usize>,
+    count: u128,
+}
+
+impl FreshSet {
+    fn contains(&self, id: usize) -> bool {
+        self.ranges
+            .range(..=id)
+            .next_back()
+            .is_some_and(|(_, &end)| id <= end)
+    }
+
+    fn insert(&mut self, start: usize, end: usize) {
+        self.ranges.insert(start, end);
+        self.count += (end - start) as u128 + 1;
+    }
+
+    fn take(&mut self, start: usize) -> usize {
+        let end = self.ranges.remove(&start).unwrap();
+        self.count -= (end - start) as u128 + 1;
+        end
+    }
+
+    /// Starts of the ranges touching `start..=end`, with `gap` ids allowed between them.
+    fn near(&self, start: usize, end: usize, gap: usize) -> Vec<usize> {
+        // ranges are sorted by both start and end, so the walk can stop early
+        self.ranges
+            .range(..=end.saturating_add(gap))
+            .rev()
+            .take_while(|&(_, &e)| e.saturating_add(gap) >= start)
+            .map(|(&s, _)| s)
+            .collect()
+    }
+
+    /// Merges `start..=end` with every range overlapping or touching it.
+    fn add(&mut self, mut start: usize, mut end: usize) {
+        for s in self.near(start, end, 1) {
+            let e = self.take(s);
+            start = start.min(s);
+            end = end.max(e);
+        }
+        self.insert(start, end);
+    }
+
+    /// Cuts `start..=end` out of the ranges overlapping it.
+    fn remove(&mut self, start: usize, end: usize) {
+        for s in self.near(start, end, 0) {
+            let e = self.take(s);
+            if s < start {
+                self.insert(s, start - 1);
+            }
+            if e > end {
+                self.insert(end + 1, e);
+            }
+        }
+    }
+
+    /// Parts of `start..=end` not covered by any range.
+    fn gaps(&self, start: usize, end: usize) -> Vec<RangeInclusive<usize>> {
+        let first = self.ranges.range(..=start).next_back().map_or(start, |(&s, _)| s);
+
+        let mut gaps = vec![];
+        // first id which may not be covered
+        let mut from = start;
+        for (&s, &e) in self.ranges.range(first..=end) {
+            if s > from {
+                gaps.push(from..=s - 1);
+            }
+            if e >= end {
+                return gaps;
+            }
+            from = from.max(e + 1);
+        }
+        gaps.push(from..=end);
+
+        gaps
+    }
+}
@@ -9,7 +9,7 @@ struct Task;
 
 impl util::Task for Task {
     type Input = MathSheet;
-    type Output = usize;
+    type Output = u128;
 
     fn solve_1(input: Self::Input) -> Self::Output {
         let mut problems = vec![];
@@ -23,7 +23,7 @@ impl util::Task for Task {
             let mut items = line.split(' ').filter(|s| !s.is_empty()).peekable();
 
             let first = items.peek().unwrap();
-            if first.contains(|c| matches!(c, '*' | '+')) {
+            if first.contains(['*', '+']) {
                 parse_line_as_operations(&mut items, &mut operations);
             } else {
                 parse_line_as_numbers(&mut items, &mut problems);
@@ -32,9 +32,9 @@ impl util::Task for Task {
 
         problems
             .into_iter()
-            .zip(operations.into_iter())
+            .zip(operations)
             .map(|p| match p.1 {
-                Operation::Add => p.0.iter().sum::<usize>(),
+                Operation::Add => p.0.iter().sum::<u128>(),
                 Operation::Product => p.0.iter().product(),
             })
             .sum()
@@ -43,19 +43,19 @@ impl util::Task for Task {
     fn solve_2(input: Self::Input) -> Self::Output {
         let homework: MathHomework = input.into();
 
-        let mut res = 0usize;
+        let mut res = 0u128;
         let mut problem = vec![];
         let mut cols = (0..homework.cols - 1).rev();
         'cols: while let Some(col) = cols.next() {
-            let mut num = 0usize;
+            let mut num = 0u128;
             for row in 0..homework.rows {
                 match homework.get(row, col) {
-                    val @ b'0'..=b'9' => num = num * 10 + (val - b'0') as usize,
+                    val @ b'0'..=b'9' => num = num * 10 + (val - b'0') as u128,
                     b' ' => continue,
                     op @ (b'+' | b'*') => {
                         res += match op {
-                            b'+' => problem.iter().sum::<usize>() + num,
-                            b'*' => problem.iter().product::<usize>() * num,
+                            b'+' => problem.iter().sum::<u128>() + num,
+                            b'*' => problem.iter().product::<u128>() * num,
                             _ => unreachable!("by outer match arm"),
                         };
                         problem.clear();
@@ -113,18 +113,16 @@ fn parse_line_as_operations<'a, 'b>(
 
 fn parse_line_as_numbers<'a, 'b>(
     line: &'a mut impl Iterator<Item = &'a str>,
-    problems: &mut Vec<Vec<usize>>,
+    problems: &mut Vec<Vec<u128>>,
 ) {
-    let mut pos = 0usize;
-    for num in line {
+    for (pos, num) in line.enumerate() {
         let problem = if let Some(problem) = problems.get_mut(pos) {
             problem
         } else {
             problems.push(vec![]);
             problems.get_mut(pos).unwrap()
         };
-        problem.push(num.parse::<usize>().unwrap());
-        pos += 1;
+        problem.push(num.parse::<u128>().unwrap());
     }
 }
 
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::io::{BufRead, BufReader, Read, Result};
-use util::TaskInput;
+use util::{BigUint, TaskInput};
 
 fn main() -> Result<()> {
     util::run::<Task>("day7/src/input.txt")
@@ -11,7 +11,7 @@ struct Task;
 
 impl util::Task for Task {
     type Input = Manifold;
-    type Output = usize;
+    type Output = BigUint;
 
     fn solve_1(mut input: Self::Input) -> Self::Output {
         for row in 0..input.rows {
@@ -19,7 +19,7 @@ impl util::Task for Task {
                 if let Some(Tile::Source) = input.get(row, col) {
                     let res = descent_splits(&mut input, (row + 1, col));
                     //println!("{input:?}");
-                    return res;
+                    return res.into();
                 }
             }
         }
@@ -62,7 +62,11 @@ fn descent_splits(manifold: &mut Manifold, (row, col): (usize, usize)) -> usize
     0
 }
 
-fn descent_timelines(manifold: &mut Manifold, (row, col): (usize, usize), tile_timelines: &mut HashMap<(usize, usize), usize>) -> usize {
+fn descent_timelines(
+    manifold: &mut Manifold,
+    (row, col): (usize, usize),
+    tile_timelines: &mut HashMap<(usize, usize), BigUint>,
+) -> BigUint {
     for next_row in row..manifold.rows {
         let Some(tile) = manifold.get_mut(next_row, col) else {
             break;
@@ -74,19 +78,19 @@ fn descent_timelines(manifold: &mut Manifold, (row, col): (usize, usize), tile_t
             Tile::Splitter => {
                 let key = (next_row, col);
                 if let Some(timelines) = tile_timelines.get(&key) {
-                    return *timelines;
+                    return timelines.clone();
                 }
 
                 let left = descent_timelines(manifold, (next_row, col - 1), tile_timelines);
                 let right = descent_timelines(manifold, (next_row, col + 1), tile_timelines);
                 let timelines = left + right;
-                tile_timelines.insert(key, timelines);
+                tile_timelines.insert(key, timelines.clone());
                 return timelines;
             }
         }
     }
 
-    1
+    BigUint::from(1u8)
 }
 
 struct Manifold {
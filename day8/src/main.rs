@@ -21,7 +21,7 @@ impl util::Task for Task {
         }
 
         let mut circuits = HashMap::new();
-        for circuit in map.circuits_per_box.into_iter().filter_map(|c| c) {
+        for circuit in map.circuits_per_box.into_iter().flatten() {
             *circuits.entry(circuit).or_insert(0) += 1;
         }
 
@@ -35,7 +35,10 @@ impl util::Task for Task {
         let mut map = DistanceMap::new(input.0);
         let mut closest = map.distances.iter().map(|(&k, &v)| (k ,v)).collect::<Vec<_>>();
         closest.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
+        let progress = util::progress::start(closest.len() as u64);
+        progress.message("connecting closest pairs");
         for ((first, second), _) in closest.iter() {
+            progress.inc(1);
             map.connect(*first, *second);
             let mut circuits = map.circuits_per_box.iter();
             if let Some(circuit) = circuits.next() && circuit.is_some() && circuits.all(|i| i == circuit) {
@@ -1,7 +1,6 @@
 use std::fmt::{Debug, Formatter};
 use std::io::{BufRead, BufReader, Read, Result};
 use std::ops::RangeInclusive;
-use std::ptr::addr_eq;
 use util::TaskInput;
 
 fn main() -> Result<()> {
@@ -34,6 +33,10 @@ impl util::Task for Task {
         let polygon = Polygon::new(input.0);
 
         for firsti in 0..polygon.points.len() {
+            if util::cancel::is_cancelled() {
+                return max;
+            }
+
             for secondi in (firsti + 1)..polygon.points.len() {
                 let first = polygon.points[firsti];
                 let second = polygon.points[secondi];
@@ -77,10 +80,8 @@ impl Line {
             if self.y_range().contains(&y) {
                 return true;
             }
-        } else if self.0.1 == y {
-            if self.x_range().contains(&x) {
-                return true;
-            }
+        } else if self.0.1 == y && self.x_range().contains(&x) {
+            return true;
         }
 
         false
@@ -90,18 +91,22 @@ impl Line {
         if self.is_vertical() {
             let x = self.0.0;
             let y_range = self.y_range();
-            if rectangle.left < x && x < rectangle.right {
-                if *y_range.start() < rectangle.bottom && *y_range.end() > rectangle.top {
-                    return true;
-                }
+            if rectangle.left < x
+                && x < rectangle.right
+                && *y_range.start() < rectangle.bottom
+                && *y_range.end() > rectangle.top
+            {
+                return true;
             }
         } else {
             let y = self.0.1;
             let x_range = self.x_range();
-            if rectangle.top < y && y < rectangle.bottom {
-                if *x_range.start() < rectangle.right && *x_range.end() > rectangle.left {
-                    return true;
-                }
+            if rectangle.top < y
+                && y < rectangle.bottom
+                && *x_range.start() < rectangle.right
+                && *x_range.end() > rectangle.left
+            {
+                return true;
             }
         }
 
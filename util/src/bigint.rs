@@ -0,0 +1,424 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Unsigned integer of arbitrary size.
+///
+/// Only what the tasks need is implemented: addition, subtraction,
+/// multiplication, comparison and decimal conversion.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    // little-endian base 2^32 limbs without trailing zeroes, zero is empty
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub const fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.normalize();
+    }
+
+    fn add_small(&mut self, value: u32) {
+        let mut carry = value as u64;
+        for limb in self.limbs.iter_mut() {
+            if carry == 0 {
+                return;
+            }
+            let sum = *limb as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Divides in place and returns the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let cur = (rem << 32) | *limb as u64;
+            *limb = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        self.normalize();
+        rem as u32
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut value: u128) -> Self {
+        let mut limbs = Vec::new();
+        while value != 0 {
+            limbs.push(value as u32);
+            value >>= 32;
+        }
+        Self { limbs }
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    Self::from(value as u128)
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u8, u16, u32, u64, usize);
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> Self::Output {
+        let (long, short) = if self.limbs.len() >= rhs.limbs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u64;
+        for (i, &limb) in long.limbs.iter().enumerate() {
+            let sum = limb as u64 + short.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+
+        BigUint { limbs }
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: BigUint) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        *self = &*self + rhs;
+    }
+}
+
+impl AddAssign for BigUint {
+    fn add_assign(&mut self, rhs: BigUint) {
+        *self += &rhs;
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if `rhs` is greater than `self`, like unsigned integers do in debug builds.
+    fn sub(self, rhs: &BigUint) -> Self::Output {
+        assert!(*self >= *rhs, "attempt to subtract with overflow");
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let mut diff = limb as i64 - rhs.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = (diff < 0) as i64;
+            if diff < 0 {
+                diff += 1 << 32;
+            }
+            limbs.push(diff as u32);
+        }
+
+        let mut res = BigUint { limbs };
+        res.normalize();
+        res
+    }
+}
+
+impl Sub for BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: BigUint) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl SubAssign<&BigUint> for BigUint {
+    fn sub_assign(&mut self, rhs: &BigUint) {
+        *self = &*self - rhs;
+    }
+}
+
+impl SubAssign for BigUint {
+    fn sub_assign(&mut self, rhs: BigUint) {
+        *self -= &rhs;
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let cur = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+
+        let mut res = BigUint { limbs };
+        res.normalize();
+        res
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: BigUint) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        *self = &*self * rhs;
+    }
+}
+
+impl MulAssign for BigUint {
+    fn mul_assign(&mut self, rhs: BigUint) {
+        *self *= &rhs;
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, x| &acc + x)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BigUint::from(1u8), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BigUint> for BigUint {
+    fn product<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::from(1u8), |acc, x| &acc * x)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigUintError);
+        }
+
+        let mut res = BigUint::zero();
+        for c in s.bytes() {
+            let digit = match c {
+                b'0'..=b'9' => c - b'0',
+                _ => return Err(ParseBigUintError),
+            };
+            res.mul_small(10);
+            res.add_small(digit as u32);
+        }
+
+        Ok(res)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigUintError;
+
+impl Display for ParseBigUintError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        let mut chunks = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(CHUNK));
+        }
+
+        let mut digits = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{chunk:09}"));
+        }
+
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn carries_into_new_limbs() {
+        let max = BigUint::from(u32::MAX);
+        assert_eq!(&max + &BigUint::from(1u8), BigUint::from(1u64 << 32));
+        assert_eq!(BigUint::from(u64::MAX) + BigUint::from(1u8), BigUint::from(1u128 << 64));
+        assert_eq!(&max * &max, BigUint::from(u32::MAX as u64 * u32::MAX as u64));
+        assert_eq!(
+            BigUint::from(u64::MAX) * BigUint::from(u64::MAX),
+            BigUint::from(u64::MAX as u128 * u64::MAX as u128)
+        );
+        assert_eq!(
+            BigUint::from(u128::MAX) * BigUint::from(u128::MAX),
+            big("115792089237316195423570985008687907852589419931798687112530834793049593217025")
+        );
+    }
+
+    #[test]
+    fn borrows_across_limbs() {
+        assert_eq!(BigUint::from(1u128 << 64) - BigUint::from(1u8), BigUint::from(u64::MAX));
+        assert_eq!(BigUint::from(1u128 << 96) - BigUint::from(1u64 << 32), BigUint::from((1u128 << 96) - (1 << 32)));
+        assert!((BigUint::from(u128::MAX) - BigUint::from(u128::MAX)).is_zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn sub_below_zero_panics() {
+        let _ = BigUint::from(1u8) - BigUint::from(1u128 << 64);
+    }
+
+    #[test]
+    fn matches_u128_arithmetic() {
+        let values = [0u128, 1, 9, 1 << 31, u32::MAX as u128, 1 << 32, 999_999_999_999, u64::MAX as u128, 1 << 100];
+        for a in values {
+            for b in values {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                if let Some(sum) = a.checked_add(b) {
+                    assert_eq!(&x + &y, BigUint::from(sum), "{a} + {b}");
+                }
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(&x * &y, BigUint::from(product), "{a} * {b}");
+                }
+                if a >= b {
+                    assert_eq!(&x - &y, BigUint::from(a - b), "{a} - {b}");
+                }
+                assert_eq!(x.cmp(&y), a.cmp(&b), "{a} cmp {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn displays_with_zero_padded_chunks() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(10u64.pow(9)).to_string(), "1000000000");
+        assert_eq!(BigUint::from(10u64.pow(18)).to_string(), "1000000000000000000");
+        assert_eq!(BigUint::from(10u64.pow(18) + 7).to_string(), "1000000000000000007");
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(format!("{:>5}", BigUint::from(42u8)), "   42");
+    }
+
+    #[test]
+    fn parses_and_displays_back() {
+        for s in ["0", "7", "4294967296", "1000000000000000000", "340282366920938463463374607431768211456"] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("000123"), BigUint::from(123u8));
+        assert!(big("0000").is_zero());
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        for s in ["", "-1", "+1", "12a", " 1", "1 ", "1_000", "0x10"] {
+            assert_eq!(s.parse::<BigUint>(), Err(ParseBigUintError), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn orders_by_limb_count_first() {
+        assert!(BigUint::from(1u64 << 32) > BigUint::from(u32::MAX));
+        assert!(BigUint::from(1u128 << 64) > BigUint::from(u64::MAX));
+        assert!(BigUint::zero() < BigUint::from(1u8));
+        assert!(BigUint::from(3u128 << 64) > BigUint::from(2u128 << 64 | u64::MAX as u128));
+        assert_eq!(big("18446744073709551616").cmp(&BigUint::from(1u128 << 64)), Ordering::Equal);
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+static CURRENT: OnceLock<Token> = OnceLock::new();
+
+/// Cooperative cancellation flag shared between the runner and a solver.
+///
+/// Solvers poll it in their hot loops and bail out early, the runner
+/// discards whatever a cancelled solve returns.
+#[derive(Clone, Default, Debug)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Token of the current run.
+pub fn token() -> &'static Token {
+    CURRENT.get_or_init(Token::new)
+}
+
+pub fn is_cancelled() -> bool {
+    token().is_cancelled()
+}
@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Result, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One runner invocation, stored as a tab separated line of the history file.
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub time: u64,
+    pub commit: String,
+    pub day: String,
+    pub part: u32,
+    pub input_hash: u64,
+    pub answer: String,
+    pub parse_secs: f64,
+    pub solve_secs: f64,
+    /// Options of the run as given on the command line, see [`crate::Options::key`].
+    pub options: String,
+}
+
+impl Run {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        Some(Run {
+            time: fields.next()?.parse().ok()?,
+            commit: fields.next()?.to_owned(),
+            day: fields.next()?.to_owned(),
+            part: fields.next()?.parse().ok()?,
+            input_hash: u64::from_str_radix(fields.next()?, 16).ok()?,
+            answer: fields.next()?.to_owned(),
+            parse_secs: fields.next()?.parse().ok()?,
+            solve_secs: fields.next()?.parse().ok()?,
+            // runs recorded before options were stored had none
+            options: fields.next().unwrap_or("").to_owned(),
+        })
+    }
+}
+
+impl Display for Run {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{:016x}\t{}\t{:.10}\t{:.10}\t{}",
+            self.time,
+            self.commit,
+            self.day,
+            self.part,
+            self.input_hash,
+            self.answer,
+            self.parse_secs,
+            self.solve_secs,
+            self.options
+        )
+    }
+}
+
+pub fn history_path() -> PathBuf {
+    PathBuf::from(".aoc/history.tsv")
+}
+
+pub fn append(run: &Run) -> Result<()> {
+    let path = history_path();
+    fs::create_dir_all(path.parent().unwrap())?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    // answers never contain tabs or newlines, but multi-line output must not break the log
+    let answer = run.answer.replace(['\t', '\n'], " ");
+    let options = run.options.replace(['\t', '\n'], " ");
+    writeln!(file, "{}", Run { answer, options, ..run.clone() })
+}
+
+/// Every recorded run of `day`, oldest first.
+pub fn load(day: &str) -> Result<Vec<Run>> {
+    let history = match fs::read_to_string(history_path()) {
+        Ok(history) => history,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+
+    Ok(history
+        .lines()
+        .filter_map(Run::parse)
+        .filter(|run| run.day == day)
+        .collect())
+}
+
+/// Short hash of `HEAD`, suffixed with `+` when the tree has local changes.
+pub fn current_commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
+    };
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) => {
+            let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+            if dirty { commit + "+" } else { commit }
+        }
+        None => "unknown".to_owned(),
+    }
+}
+
+/// FNV-1a, stable across builds unlike `DefaultHasher`.
+pub fn hash_input(input: &[u8]) -> u64 {
+    input.iter().fold(0xcbf29ce484222325u64, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
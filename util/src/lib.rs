@@ -1,9 +1,19 @@
-use std::env::args;
-use std::fmt::Debug;
-use std::fs::File;
-use std::io::{Error, Read};
-use std::ops::Deref;
-use std::time::Instant;
+mod bigint;
+pub mod cancel;
+pub mod history;
+mod options;
+pub mod progress;
+
+use std::env::{args, current_exe};
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Read};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub use bigint::{BigUint, ParseBigUintError};
+pub use options::{Options, options};
 
 pub trait Task {
     type Input;
@@ -27,22 +37,96 @@ where
 
 pub fn run<T: Task>(input_path: &str) -> std::io::Result<()>
 where
-    <T as Task>::Input: TaskInput,
-    <T as Task>::Output: Debug
+    <T as Task>::Input: TaskInput + Send + 'static,
+    <T as Task>::Output: Display + Send + 'static,
 {
-    let args = args().skip(1).collect::<Vec<_>>();
+    let options = options::set(Options::parse(args().skip(1))?);
+    let part = options.part().unwrap();
+    let timeout = options.parse_value::<f64>("timeout")?.map(Duration::from_secs_f64);
+
     let parse_instant = Instant::now();
     let input = T::Input::read_from_file(input_path)?;
     let parse_elapsed = parse_instant.elapsed();
     let instant = Instant::now();
-    let res = match args.first().map(Deref::deref) {
-        Some("1") => T::solve_1(input),
-        Some("2") => T::solve_2(input),
-        _ => return Err(Error::other("Expected args to be 1 or 2")),
-    };
+    let res = solve_on_worker::<T>(part, input, timeout)?;
     let elapsed = instant.elapsed();
-    println!("{:?}", res);
+    println!("{}", res);
     println!("elapsed for parse: {:.10}", parse_elapsed.as_secs_f64());
     println!("elapsed: {:.10}", elapsed.as_secs_f64());
+
+    let run = history::Run {
+        time: history::now(),
+        commit: history::current_commit(),
+        day: day_name(input_path),
+        part,
+        input_hash: history::hash_input(&fs::read(input_path)?),
+        answer: res.to_string(),
+        parse_secs: parse_elapsed.as_secs_f64(),
+        solve_secs: elapsed.as_secs_f64(),
+        options: options.key(),
+    };
+    if let Err(err) = history::append(&run) {
+        eprintln!("failed to record run history: {err}");
+    }
+
     Ok(())
 }
+
+/// Runs the solver on its own thread, so the runner can give up after `timeout`.
+fn solve_on_worker<T: Task>(part: u32, input: T::Input, timeout: Option<Duration>) -> std::io::Result<T::Output>
+where
+    <T as Task>::Input: Send + 'static,
+    <T as Task>::Output: Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        let res = match part {
+            1 => T::solve_1(input),
+            _ => T::solve_2(input),
+        };
+        let _ = sender.send(res);
+    });
+
+    // wake up regularly to draw progress until the answer or the deadline arrives
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let mut renderer = progress::Renderer::new();
+    let res = loop {
+        let tick = match deadline {
+            Some(deadline) => progress::Renderer::TICK.min(deadline.saturating_duration_since(Instant::now())),
+            None => progress::Renderer::TICK,
+        };
+        match receiver.recv_timeout(tick) {
+            Ok(res) => break Ok(res),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break Err(None),
+            Err(mpsc::RecvTimeoutError::Timeout) if deadline.is_some_and(|d| Instant::now() >= d) => {
+                break Err(timeout);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => renderer.render(),
+        }
+    };
+    renderer.finish();
+
+    match res {
+        Ok(res) => Ok(res),
+        Err(Some(timeout)) => {
+            // the solver may still be polling, but nobody waits for its answer anymore
+            cancel::token().cancel();
+            Err(Error::new(
+                ErrorKind::TimedOut,
+                format!("solver timed out after {:.3}s", timeout.as_secs_f64()),
+            ))
+        }
+        Err(None) => match worker.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => Err(Error::other("solver finished without an answer")),
+        },
+    }
+}
+
+/// Name of the running day binary, e.g. `day3`.
+fn day_name(input_path: &str) -> String {
+    current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| input_path.split('/').next().unwrap_or(input_path).to_owned())
+}
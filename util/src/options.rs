@@ -0,0 +1,89 @@
+use std::io::{Error, Result};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+/// Command line of a day binary: the part followed by `--name value`,
+/// `--name=value` options and bare `--flag`s.
+#[derive(Default, Debug)]
+pub struct Options {
+    part: Option<u32>,
+    values: Vec<(String, Option<String>)>,
+}
+
+impl Options {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter().peekable();
+        let part = match args.next().as_deref() {
+            Some("1") => Some(1),
+            Some("2") => Some(2),
+            _ => return Err(Error::other("Expected args to be 1 or 2")),
+        };
+
+        let mut values = vec![];
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(Error::other(format!("unexpected argument {arg}")));
+            };
+            if let Some((name, value)) = name.split_once('=') {
+                values.push((name.to_owned(), Some(value.to_owned())));
+            } else {
+                let value = args.next_if(|next| !next.starts_with("--"));
+                values.push((name.to_owned(), value));
+            }
+        }
+
+        Ok(Options { part, values })
+    }
+
+    /// The options as `--name value` in the given order, without `--timeout`
+    /// which changes neither the answer nor the time of a run.
+    pub fn key(&self) -> String {
+        self.values
+            .iter()
+            .filter(|(name, _)| name != "timeout")
+            .map(|(name, value)| match value {
+                Some(value) => format!("--{name} {value}"),
+                None => format!("--{name}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn part(&self) -> Option<u32> {
+        self.part
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.values.iter().any(|(n, _)| n == name)
+    }
+
+    /// Parses the value of `--name`, `None` if it was not given.
+    pub fn parse_value<T: FromStr>(&self, name: &str) -> Result<Option<T>> {
+        self.get(name)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| Error::other(format!("invalid value {v:?} for --{name}")))
+            })
+            .transpose()
+    }
+}
+
+/// Options of the current run, empty until the runner has parsed the command line.
+pub fn options() -> &'static Options {
+    OPTIONS.get_or_init(Options::default)
+}
+
+pub(crate) fn set(parsed: Options) -> &'static Options {
+    let _ = OPTIONS.set(parsed);
+    options()
+}
@@ -0,0 +1,96 @@
+use std::io::{IsTerminal, Write, stderr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+static CURRENT: Mutex<Option<Arc<State>>> = Mutex::new(None);
+
+#[derive(Default)]
+struct State {
+    total: AtomicU64,
+    done: AtomicU64,
+    message: Mutex<String>,
+}
+
+/// Progress of the running solver, shown by the runner while it waits.
+///
+/// Cheap to update from hot loops: `inc` is a single relaxed atomic add.
+#[derive(Clone)]
+pub struct Progress(Arc<State>);
+
+impl Progress {
+    pub fn inc(&self, n: u64) {
+        self.0.done.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn set_total(&self, total: u64) {
+        self.0.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn message(&self, message: impl Into<String>) {
+        *self.0.message.lock().unwrap() = message.into();
+    }
+}
+
+/// Starts reporting progress towards `total`, replacing the previous handle.
+pub fn start(total: u64) -> Progress {
+    let state = Arc::new(State::default());
+    state.total.store(total, Ordering::Relaxed);
+    *CURRENT.lock().unwrap() = Some(Arc::clone(&state));
+    Progress(state)
+}
+
+/// Draws the current progress on stderr: a bar redrawn in place on a
+/// terminal, a log line every `LOG_INTERVAL` otherwise.
+pub(crate) struct Renderer {
+    tty: bool,
+    last_log: Instant,
+    last_done: Option<u64>,
+    drawn: bool,
+}
+
+impl Renderer {
+    pub(crate) const TICK: Duration = Duration::from_millis(100);
+    const LOG_INTERVAL: Duration = Duration::from_secs(2);
+    const BAR_WIDTH: u64 = 30;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            tty: stderr().is_terminal(),
+            last_log: Instant::now(),
+            last_done: None,
+            drawn: false,
+        }
+    }
+
+    pub(crate) fn render(&mut self) {
+        let Some(state) = CURRENT.lock().unwrap().clone() else {
+            return;
+        };
+        let total = state.total.load(Ordering::Relaxed);
+        let done = state.done.load(Ordering::Relaxed);
+        let message = state.message.lock().unwrap().clone();
+        let percent = (done.min(total) * 100).checked_div(total).unwrap_or(0);
+
+        let mut err = stderr().lock();
+        if self.tty {
+            let filled = (done.min(total) * Self::BAR_WIDTH).checked_div(total).unwrap_or(0);
+            let bar = "#".repeat(filled as usize) + &".".repeat((Self::BAR_WIDTH - filled) as usize);
+            let _ = write!(err, "\r\x1b[2K[{bar}] {done}/{total} {percent}% {message}");
+            self.drawn = true;
+        } else if self.last_log.elapsed() >= Self::LOG_INTERVAL && self.last_done != Some(done) {
+            let _ = writeln!(err, "progress: {done}/{total} ({percent}%) {message}");
+            self.last_log = Instant::now();
+            self.last_done = Some(done);
+        }
+        let _ = err.flush();
+    }
+
+    pub(crate) fn finish(&mut self) {
+        if self.drawn {
+            let _ = write!(stderr(), "\r\x1b[2K");
+            self.drawn = false;
+        }
+        *CURRENT.lock().unwrap() = None;
+    }
+}